@@ -0,0 +1,60 @@
+/* C interface to the delaunay crate, built from delaunay-ffi as a shared or static
+ * library. Every function returns a DelaunayStatus; results come back through
+ * out-parameters. */
+
+#ifndef DELAUNAY_H
+#define DELAUNAY_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum DelaunayStatus {
+    DELAUNAY_OK = 0,
+    /* a pointer argument was null */
+    DELAUNAY_NULL_POINTER = 1,
+    /* a coordinate was NaN or infinite */
+    DELAUNAY_NOT_FINITE = 2,
+    /* more points than 32-bit indices can address */
+    DELAUNAY_TOO_MANY_POINTS = 3,
+    /* the triangulator panicked; this is a bug */
+    DELAUNAY_PANIC = 4
+} DelaunayStatus;
+
+/* the value of a missing twin in delaunay_half_edges */
+#define DELAUNAY_NO_TWIN UINT32_MAX
+
+typedef struct DelaunayTriangulation DelaunayTriangulation;
+
+/* Triangulates count points given as x0, y0, x1, y1, ... in coords, and stores a new
+ * handle in *out, to be released with delaunay_free. On error *out is set to NULL.
+ * coords may be NULL when count is zero. Fewer than three points, or points all on a
+ * line, give no triangles or half-edges; the hull then lists the distinct points in
+ * order along the line. */
+DelaunayStatus delaunay_triangulate(const double *coords, size_t count,
+                                    DelaunayTriangulation **out);
+
+/* The points of every triangle, three indices each, in (*data)[0..*len]. The array
+ * lives as long as the handle. */
+DelaunayStatus delaunay_triangles(const DelaunayTriangulation *triangulation,
+                                  const uint32_t **data, size_t *len);
+
+/* The twin of every half-edge, or DELAUNAY_NO_TWIN on the hull. */
+DelaunayStatus delaunay_half_edges(const DelaunayTriangulation *triangulation,
+                                   const uint32_t **data, size_t *len);
+
+/* The points on the convex hull in order. */
+DelaunayStatus delaunay_hull(const DelaunayTriangulation *triangulation,
+                             const uint32_t **data, size_t *len);
+
+/* Releases a handle. NULL is ignored. */
+void delaunay_free(DelaunayTriangulation *triangulation);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,278 @@
+//! A C interface to the `delaunay` crate, declared in `include/delaunay.h`.
+//!
+//! Every function returns a [`Status`]; results come back through out-parameters, and
+//! a panic inside the triangulator is caught and reported as [`Status::Panic`] rather
+//! than unwinding into C.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use delaunay::{triangulate, Vertex};
+
+/// What went wrong, or `Ok`. Mirrors `DelaunayStatus` in the header.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    /// A pointer argument was null.
+    NullPointer = 1,
+    /// A coordinate was NaN or infinite.
+    NotFinite = 2,
+    /// More points than 32-bit indices can address.
+    TooManyPoints = 3,
+    /// The triangulator panicked; this is a bug.
+    Panic = 4,
+}
+
+/// The value of a missing twin in [`delaunay_half_edges`].
+pub const NO_TWIN: u32 = u32::MAX;
+
+/// An opaque triangulation, with its arrays already flattened to 32-bit indices.
+pub struct Triangulation {
+    triangles: Vec<u32>,
+    half_edges: Vec<u32>,
+    hull: Vec<u32>,
+}
+
+impl Triangulation {
+    fn new(points: Vec<Vertex>) -> Self {
+        if let Some(hull) = line_hull(&points) {
+            return Self {
+                triangles: Vec::new(),
+                half_edges: Vec::new(),
+                hull,
+            };
+        }
+        let (t, _) = triangulate(points);
+        let index = |v: usize| v as u32;
+        Self {
+            triangles: t.triangles().iter().map(|v| index(v.get())).collect(),
+            half_edges: t
+                .half_edges()
+                .iter()
+                .map(|e| e.map_or(NO_TWIN, |e| index(e.get())))
+                .collect(),
+            hull: t.hull().iter().map(|v| index(v.get())).collect(),
+        }
+    }
+}
+
+// for fewer than three points or points all on a line, which have no triangles, the
+// distinct points in order along the line
+fn line_hull(points: &[Vertex]) -> Option<Vec<u32>> {
+    let Some(&a) = points.first() else {
+        return Some(Vec::new());
+    };
+    let d = points
+        .iter()
+        .find(|&&p| p != a)
+        .map_or(Vertex::ZERO, |&b| b - a);
+    let cross = |p: Vertex| d.x() * (p.y() - a.y()) - d.y() * (p.x() - a.x());
+    if points.len() >= 3 && points.iter().any(|&p| cross(p) != 0.0) {
+        return None;
+    }
+
+    let along = |i: &u32| {
+        let p = points[*i as usize] - a;
+        p.x() * d.x() + p.y() * d.y()
+    };
+    let mut hull = (0..points.len() as u32).collect::<Vec<_>>();
+    hull.sort_by(|i, j| along(i).total_cmp(&along(j)));
+    hull.dedup_by(|i, j| points[*i as usize] == points[*j as usize]);
+    Some(hull)
+}
+
+// runs `f`, turning a panic into a status
+fn guard(f: impl FnOnce() -> Status) -> Status {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(Status::Panic)
+}
+
+/// Triangulates `count` points given as `x0, y0, x1, y1, ...` in `coords`, and stores
+/// a new handle in `out`, to be released with [`delaunay_free`]. On error `out` is set
+/// to null. `coords` may be null when `count` is zero.
+///
+/// Fewer than three points, or points all on a line, give no triangles or half-edges;
+/// the hull then lists the distinct points in order along the line.
+///
+/// # Safety
+///
+/// `coords` must point to `2 * count` doubles and `out` to writable storage for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn delaunay_triangulate(
+    coords: *const f64,
+    count: usize,
+    out: *mut *mut Triangulation,
+) -> Status {
+    if out.is_null() {
+        return Status::NullPointer;
+    }
+    *out = ptr::null_mut();
+    if coords.is_null() && count > 0 {
+        return Status::NullPointer;
+    }
+    // room for the missing-twin value among the half-edge indices, at most 6n of them
+    if count >= (NO_TWIN / 6) as usize {
+        return Status::TooManyPoints;
+    }
+    let coords = if count == 0 {
+        &[][..]
+    } else {
+        slice::from_raw_parts(coords, 2 * count)
+    };
+    if !coords.iter().all(|c| c.is_finite()) {
+        return Status::NotFinite;
+    }
+
+    guard(|| {
+        let points = coords
+            .chunks_exact(2)
+            .map(|xy| Vertex::new(xy[0], xy[1]))
+            .collect();
+        *out = Box::into_raw(Box::new(Triangulation::new(points)));
+        Status::Ok
+    })
+}
+
+// hands out one of the arrays of a handle
+unsafe fn array(
+    triangulation: *const Triangulation,
+    data: *mut *const u32,
+    len: *mut usize,
+    field: fn(&Triangulation) -> &[u32],
+) -> Status {
+    if triangulation.is_null() || data.is_null() || len.is_null() {
+        return Status::NullPointer;
+    }
+    let values = field(&*triangulation);
+    *data = values.as_ptr();
+    *len = values.len();
+    Status::Ok
+}
+
+/// The points of every triangle, three indices each, in `data[0..len]`.
+///
+/// # Safety
+///
+/// `triangulation` must come from [`delaunay_triangulate`] and not be freed yet; the
+/// array lives as long as it does. `data` and `len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn delaunay_triangles(
+    triangulation: *const Triangulation,
+    data: *mut *const u32,
+    len: *mut usize,
+) -> Status {
+    array(triangulation, data, len, |t| &t.triangles)
+}
+
+/// The twin of every half-edge, or [`NO_TWIN`] on the hull, in `data[0..len]`.
+///
+/// # Safety
+///
+/// As for [`delaunay_triangles`].
+#[no_mangle]
+pub unsafe extern "C" fn delaunay_half_edges(
+    triangulation: *const Triangulation,
+    data: *mut *const u32,
+    len: *mut usize,
+) -> Status {
+    array(triangulation, data, len, |t| &t.half_edges)
+}
+
+/// The points on the convex hull in order, in `data[0..len]`.
+///
+/// # Safety
+///
+/// As for [`delaunay_triangles`].
+#[no_mangle]
+pub unsafe extern "C" fn delaunay_hull(
+    triangulation: *const Triangulation,
+    data: *mut *const u32,
+    len: *mut usize,
+) -> Status {
+    array(triangulation, data, len, |t| &t.hull)
+}
+
+/// Releases a handle. Null is ignored.
+///
+/// # Safety
+///
+/// `triangulation` must come from [`delaunay_triangulate`] and not be freed already.
+#[no_mangle]
+pub unsafe extern "C" fn delaunay_free(triangulation: *mut Triangulation) {
+    if !triangulation.is_null() {
+        drop(Box::from_raw(triangulation));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let coords = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.5, 0.4];
+        let mut t = ptr::null_mut();
+        unsafe {
+            assert_eq!(delaunay_triangulate(coords.as_ptr(), 5, &mut t), Status::Ok);
+            let (mut data, mut len) = (ptr::null(), 0);
+            assert_eq!(delaunay_triangles(t, &mut data, &mut len), Status::Ok);
+            assert_eq!(len, 12);
+            assert_eq!(delaunay_half_edges(t, &mut data, &mut len), Status::Ok);
+            let twins = slice::from_raw_parts(data, len);
+            assert_eq!(twins.iter().filter(|&&e| e == NO_TWIN).count(), 4);
+            assert_eq!(delaunay_hull(t, &mut data, &mut len), Status::Ok);
+            assert_eq!(len, 4);
+            delaunay_free(t);
+        }
+    }
+
+    #[test]
+    fn test_degenerate() {
+        let hull = |coords: &[f64]| unsafe {
+            let mut t = ptr::null_mut();
+            let pointer = if coords.is_empty() {
+                ptr::null()
+            } else {
+                coords.as_ptr()
+            };
+            assert_eq!(
+                delaunay_triangulate(pointer, coords.len() / 2, &mut t),
+                Status::Ok
+            );
+            let (mut data, mut len) = (ptr::null(), 0);
+            assert_eq!(delaunay_triangles(t, &mut data, &mut len), Status::Ok);
+            assert_eq!(len, 0);
+            assert_eq!(delaunay_half_edges(t, &mut data, &mut len), Status::Ok);
+            assert_eq!(len, 0);
+            assert_eq!(delaunay_hull(t, &mut data, &mut len), Status::Ok);
+            let hull = slice::from_raw_parts(data, len).to_vec();
+            delaunay_free(t);
+            hull
+        };
+        assert_eq!(hull(&[]), []);
+        assert_eq!(hull(&[1.0, 2.0]), [0]);
+        assert_eq!(hull(&[1.0, 2.0, 1.0, 2.0]), [0]);
+        assert_eq!(hull(&[1.0, 1.0, 0.0, 0.0, 2.0, 2.0, 1.0, 1.0]), [2, 0, 1]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut t = ptr::null_mut();
+        unsafe {
+            let nan = [0.0, f64::NAN];
+            assert_eq!(
+                delaunay_triangulate(nan.as_ptr(), 1, &mut t),
+                Status::NotFinite
+            );
+            assert!(t.is_null());
+            assert_eq!(
+                delaunay_triangulate(ptr::null(), 3, &mut t),
+                Status::NullPointer
+            );
+            let (mut data, mut len) = (ptr::null(), 0);
+            assert_eq!(delaunay_hull(t, &mut data, &mut len), Status::NullPointer);
+            assert_eq!(guard(|| panic!("inside")), Status::Panic);
+        }
+    }
+}
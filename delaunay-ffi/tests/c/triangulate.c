@@ -0,0 +1,70 @@
+/* Triangulates a small grid through the C interface and checks the arrays agree. */
+
+#include <stdio.h>
+
+#include "delaunay.h"
+
+#define CHECK(condition)                                                   \
+    do {                                                                   \
+        if (!(condition)) {                                                \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #condition); \
+            return 1;                                                      \
+        }                                                                  \
+    } while (0)
+
+int main(void) {
+    double coords[2 * 16];
+    for (int k = 0; k < 16; k++) {
+        coords[2 * k] = k % 4 + 0.01 * k;
+        coords[2 * k + 1] = k / 4 - 0.013 * k;
+    }
+
+    DelaunayTriangulation *t = NULL;
+    CHECK(delaunay_triangulate(coords, 16, &t) == DELAUNAY_OK);
+    CHECK(t != NULL);
+
+    const uint32_t *triangles, *half_edges, *hull;
+    size_t triangles_len, half_edges_len, hull_len;
+    CHECK(delaunay_triangles(t, &triangles, &triangles_len) == DELAUNAY_OK);
+    CHECK(delaunay_half_edges(t, &half_edges, &half_edges_len) == DELAUNAY_OK);
+    CHECK(delaunay_hull(t, &hull, &hull_len) == DELAUNAY_OK);
+
+    /* 2n - h - 2 triangles, and one half-edge without a twin per hull edge */
+    CHECK(triangles_len == 3 * (2 * 16 - hull_len - 2));
+    CHECK(half_edges_len == triangles_len);
+    size_t boundary = 0;
+    for (size_t e = 0; e < half_edges_len; e++) {
+        CHECK(triangles[e] < 16);
+        if (half_edges[e] == DELAUNAY_NO_TWIN) {
+            boundary++;
+        } else {
+            CHECK(half_edges[half_edges[e]] == e);
+        }
+    }
+    CHECK(boundary == hull_len);
+    delaunay_free(t);
+
+    /* no points, and points on a line, have no triangles */
+    CHECK(delaunay_triangulate(NULL, 0, &t) == DELAUNAY_OK);
+    CHECK(delaunay_triangles(t, &triangles, &triangles_len) == DELAUNAY_OK);
+    CHECK(delaunay_hull(t, &hull, &hull_len) == DELAUNAY_OK);
+    CHECK(triangles_len == 0 && hull_len == 0);
+    delaunay_free(t);
+    double line[6] = {1.0, 1.0, 0.0, 0.0, 2.0, 2.0};
+    CHECK(delaunay_triangulate(line, 3, &t) == DELAUNAY_OK);
+    CHECK(delaunay_triangles(t, &triangles, &triangles_len) == DELAUNAY_OK);
+    CHECK(delaunay_hull(t, &hull, &hull_len) == DELAUNAY_OK);
+    CHECK(triangles_len == 0 && hull_len == 3);
+    CHECK(hull[0] == 2 && hull[1] == 0 && hull[2] == 1);
+    delaunay_free(t);
+
+    double nan[2] = {0.0, 0.0 / 0.0};
+    CHECK(delaunay_triangulate(nan, 1, &t) == DELAUNAY_NOT_FINITE);
+    CHECK(t == NULL);
+    CHECK(delaunay_triangulate(coords, 16, NULL) == DELAUNAY_NULL_POINTER);
+    CHECK(delaunay_hull(NULL, &hull, &hull_len) == DELAUNAY_NULL_POINTER);
+    delaunay_free(NULL);
+
+    puts("ok");
+    return 0;
+}
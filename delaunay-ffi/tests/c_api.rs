@@ -0,0 +1,43 @@
+//! Compiles `tests/c/triangulate.c` against the header and the static library and runs it.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn test_c_program() {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let scratch = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+
+    // cargo test only builds the rlib, so build the static library on the side,
+    // in its own target directory to stay clear of the lock on this one
+    let built = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--manifest-path"])
+        .arg(manifest.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(scratch.join("ffi"))
+        .status()
+        .unwrap();
+    assert!(built.success());
+    let library = scratch.join("ffi/debug/libdelaunay_ffi.a");
+
+    let program = scratch.join("triangulate");
+    let compiled = Command::new(env::var("CC").unwrap_or_else(|_| "cc".into()))
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-I"])
+        .arg(manifest.join("include"))
+        .arg(manifest.join("tests/c/triangulate.c"))
+        .arg(&library)
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(compiled.success());
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(output.stdout, b"ok\n");
+}
@@ -0,0 +1,346 @@
+//! Points from CSV, whitespace-separated text or JSON.
+
+use delaunay::Vertex;
+
+/// How the input is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// `x,y` per line, optionally after a header line, extra columns ignored.
+    Csv,
+    /// `x y` per line, `#` starting a comment, extra columns ignored.
+    Text,
+    /// An array of `[x, y]` pairs or of `{"x": .., "y": ..}` objects.
+    Json,
+}
+
+impl InputFormat {
+    /// JSON if it opens with a bracket, CSV if its first line has a comma, text otherwise.
+    pub fn guess(input: &str) -> Self {
+        let input = input.trim_start();
+        if input.starts_with(['[', '{']) {
+            InputFormat::Json
+        } else if input.lines().next().is_some_and(|line| line.contains(',')) {
+            InputFormat::Csv
+        } else {
+            InputFormat::Text
+        }
+    }
+}
+
+pub fn parse(input: &str, format: InputFormat) -> Result<Vec<Vertex>, String> {
+    match format {
+        InputFormat::Csv => parse_lines(input, |line| line.split(',').map(str::trim), true),
+        InputFormat::Text => parse_lines(
+            input,
+            |line| line.split('#').next().unwrap_or("").split_whitespace(),
+            false,
+        ),
+        InputFormat::Json => parse_json(input),
+    }
+}
+
+/// Rejects points that have no triangulation: fewer than three, all on a line, or with
+/// coordinates that are not finite or too large to compute circumcircles with.
+pub fn check(points: &[Vertex]) -> Result<(), String> {
+    if points.len() < 3 {
+        return Err(format!("need at least 3 points, got {}", points.len()));
+    }
+    let out_of_range = |c: f64| !c.is_finite() || c.abs() > 1e100;
+    if let Some(i) = points
+        .iter()
+        .position(|p| out_of_range(p.x()) || out_of_range(p.y()))
+    {
+        return Err(format!(
+            "point {i}: coordinates must be finite and within ±1e100"
+        ));
+    }
+
+    let a = points[0];
+    let d = points
+        .iter()
+        .find(|&&p| p != a)
+        .map_or(Vertex::ZERO, |&b| b - a);
+    if points
+        .iter()
+        .all(|&p| d.x() * (p.y() - a.y()) == d.y() * (p.x() - a.x()))
+    {
+        return Err("all points are on a line".to_string());
+    }
+    Ok(())
+}
+
+fn parse_lines<'a, F, I>(input: &'a str, split: F, header: bool) -> Result<Vec<Vertex>, String>
+where
+    F: Fn(&'a str) -> I,
+    I: Iterator<Item = &'a str>,
+{
+    let mut points = Vec::new();
+    for (number, line) in input.lines().enumerate() {
+        let mut fields = split(line).filter(|field| !field.is_empty());
+        let (Some(x), Some(y)) = (fields.next(), fields.next()) else {
+            if split(line).all(str::is_empty) {
+                continue;
+            }
+            return Err(format!("line {}: expected two coordinates", number + 1));
+        };
+        match (x.parse(), y.parse()) {
+            (Ok(x), Ok(y)) => points.push(Vertex::new(x, y)),
+            // a header names the columns
+            _ if header && number == 0 => {}
+            _ => return Err(format!("line {}: bad coordinates {x:?}, {y:?}", number + 1)),
+        }
+    }
+    Ok(points)
+}
+
+fn parse_json(input: &str) -> Result<Vec<Vertex>, String> {
+    let mut parser = Json {
+        input,
+        at: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_space();
+    if parser.at < input.len() {
+        return Err(parser.error("trailing characters"));
+    }
+
+    let Value::Array(items) = value else {
+        return Err("expected an array of points".to_string());
+    };
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let xy = match item {
+                Value::Array(xy) => xy.first().zip(xy.get(1)),
+                Value::Object(fields) => {
+                    let field = |name| fields.iter().find(|(key, _)| key == name).map(|(_, v)| v);
+                    field("x").zip(field("y"))
+                }
+                _ => None,
+            };
+            match xy {
+                Some((&Value::Number(x), &Value::Number(y))) => Ok(Vertex::new(x, y)),
+                _ => Err(format!(
+                    "point {i}: expected [x, y] or {{\"x\": .., \"y\": ..}}"
+                )),
+            }
+        })
+        .collect()
+}
+
+enum Value {
+    Number(f64),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+    /// A string, boolean or null.
+    Other,
+}
+
+// a recursive descent parser for the whole of JSON, points being all we look at
+struct Json<'a> {
+    input: &'a str,
+    at: usize,
+    // arrays and objects open around the current value
+    depth: usize,
+}
+
+// deeper input is refused rather than recursed into until the stack runs out
+const MAX_DEPTH: usize = 64;
+
+impl Json<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("JSON at byte {}: {message}", self.at)
+    }
+
+    fn skip_space(&mut self) {
+        let rest = &self.input[self.at..];
+        self.at += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.at).copied()
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        self.skip_space();
+        if self.input[self.at..].starts_with(token) {
+            self.at += token.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {token:?}")))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_space();
+        if matches!(self.peek(), Some(b'[' | b'{')) && self.depth == MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        match self.peek() {
+            Some(b'[') => {
+                self.at += 1;
+                let items = self.sequence(b']', Self::value)?;
+                Ok(Value::Array(items))
+            }
+            Some(b'{') => {
+                self.at += 1;
+                let fields = self.sequence(b'}', |json| {
+                    json.skip_space();
+                    let key = json.string()?;
+                    json.expect(":")?;
+                    Ok((key, json.value()?))
+                })?;
+                Ok(Value::Object(fields))
+            }
+            Some(b'"') => self.string().map(|_| Value::Other),
+            Some(b't') => self.expect("true").map(|_| Value::Other),
+            Some(b'f') => self.expect("false").map(|_| Value::Other),
+            Some(b'n') => self.expect("null").map(|_| Value::Other),
+            Some(_) => self.number(),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    // items separated by commas up to `close`, the opening bracket already consumed
+    fn sequence<T>(
+        &mut self,
+        close: u8,
+        mut item: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        let mut items = Vec::new();
+        self.skip_space();
+        if self.peek() == Some(close) {
+            self.at += 1;
+            return Ok(items);
+        }
+        self.depth += 1;
+        loop {
+            items.push(item(self)?);
+            self.skip_space();
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(c) if c == close => {
+                    self.at += 1;
+                    self.depth -= 1;
+                    return Ok(items);
+                }
+                _ => return Err(self.error("expected a comma or a closing bracket")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let rest = &self.input[self.at..];
+        let len = rest
+            .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+            .unwrap_or(rest.len());
+        let number = rest[..len]
+            .parse()
+            .map_err(|_| self.error("expected a value"))?;
+        self.at += len;
+        Ok(Value::Number(number))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.peek() != Some(b'"') {
+            return Err(self.error("expected a string"));
+        }
+        self.at += 1;
+        let mut string = String::new();
+        let mut chars = self.input[self.at..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.at += i + 1;
+                    return Ok(string);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('r') => string.push('\r'),
+                    Some('b') => string.push('\u{8}'),
+                    Some('f') => string.push('\u{c}'),
+                    Some('u') => {
+                        let hex = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+                        let code = u32::from_str_radix(&hex, 16).ok();
+                        string.push(code.and_then(char::from_u32).unwrap_or('\u{fffd}'));
+                    }
+                    Some(c) => string.push(c),
+                    None => break,
+                },
+                c => string.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats() {
+        let expected = vec![Vertex::new(0.0, 1.5), Vertex::new(-2.0, 3e2)];
+        let csv = "x, y, label\n0, 1.5, a\n\n-2,3e2,b\n";
+        let text = "# x y\n0 1.5\n  -2\t300 extra # comment\n";
+        let json = r#"[[0, 1.5], {"y": 3e2, "x": -2, "name": "b\"é"}]"#;
+        for (input, format) in [
+            (csv, InputFormat::Csv),
+            (text, InputFormat::Text),
+            (json, InputFormat::Json),
+        ] {
+            assert_eq!(InputFormat::guess(input), format);
+            assert_eq!(parse(input, format), Ok(expected.clone()));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse("0 0\n1 one\n", InputFormat::Text),
+            Err("line 2: bad coordinates \"1\", \"one\"".to_string())
+        );
+        assert_eq!(
+            parse("x,y\n1\n", InputFormat::Csv),
+            Err("line 2: expected two coordinates".to_string())
+        );
+        assert!(parse("[[0, 1], [2]]", InputFormat::Json).is_err());
+        assert!(parse("[[0, 1]] x", InputFormat::Json).is_err());
+        assert!(parse(r#"{"points": []}"#, InputFormat::Json).is_err());
+        assert_eq!(
+            parse(&"[".repeat(200_000), InputFormat::Json),
+            Err("JSON at byte 64: nested too deeply".to_string())
+        );
+        // as deep as is allowed
+        let nested = format!("{}{}", "[".repeat(64), "]".repeat(64));
+        let mut json = Json {
+            input: &nested,
+            at: 0,
+            depth: 0,
+        };
+        assert!(json.value().is_ok());
+    }
+
+    #[test]
+    fn test_check() {
+        let points = |text| parse(text, InputFormat::Text).unwrap();
+        assert_eq!(
+            check(&points("")),
+            Err("need at least 3 points, got 0".to_string())
+        );
+        assert!(check(&points("0 0\n1 1\n")).is_err());
+        assert_eq!(
+            check(&points("0 0\n0 0\n1 1\n2 2\n")),
+            Err("all points are on a line".to_string())
+        );
+        assert_eq!(
+            check(&points("0 0\n1e300 0\n0 1e300\n")),
+            Err("point 1: coordinates must be finite and within ±1e100".to_string())
+        );
+        assert!(check(&points("0 0\ninf 0\n0 1\n")).is_err());
+        assert_eq!(check(&points("0 0\n1 0\n0 1\n")), Ok(()));
+    }
+}
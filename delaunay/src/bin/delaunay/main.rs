@@ -0,0 +1,172 @@
+//! Triangulates points from a file or stdin, for use in shell pipelines.
+
+mod input;
+mod output;
+
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+use delaunay::triangulate;
+
+use input::InputFormat;
+use output::{Content, OutputFormat};
+
+const USAGE: &str = "\
+usage: delaunay [options] [file]
+
+Triangulates the points in file, or on stdin, and writes the result to stdout. It
+takes at least three points, not all on one line, with finite coordinates up to 1e100.
+
+options:
+  -f, --from <csv|text|json>     input format, guessed from the input by default
+  -w, --write <triangles|edges|hull|voronoi>
+                                 what to write, triangles by default
+  -t, --to <text|csv|json>       output format, text by default
+  -o, --output <file>            write to file instead of stdout
+  -s, --stats                    print quality statistics to stderr
+  -h, --help                     print this help
+
+Triangles, edges and the hull are written as point indices, counting from 0 in input
+order. Voronoi cells are written as the site index followed by the corners of its cell;
+cells of points on the hull are unbounded and only list their finite corners.";
+
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    input: Option<String>,
+    from: Option<InputFormat>,
+    content: Option<Content>,
+    to: Option<OutputFormat>,
+    output: Option<String>,
+    stats: bool,
+    help: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{name} needs a value"));
+        match arg.as_str() {
+            "-f" | "--from" => {
+                options.from = Some(match value(&arg)?.as_str() {
+                    "csv" => InputFormat::Csv,
+                    "text" => InputFormat::Text,
+                    "json" => InputFormat::Json,
+                    other => return Err(format!("unknown input format {other:?}")),
+                })
+            }
+            "-w" | "--write" => {
+                options.content = Some(match value(&arg)?.as_str() {
+                    "triangles" => Content::Triangles,
+                    "edges" => Content::Edges,
+                    "hull" => Content::Hull,
+                    "voronoi" => Content::Voronoi,
+                    other => return Err(format!("unknown output {other:?}")),
+                })
+            }
+            "-t" | "--to" => {
+                options.to = Some(match value(&arg)?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    other => return Err(format!("unknown output format {other:?}")),
+                })
+            }
+            "-o" | "--output" => options.output = Some(value(&arg)?),
+            "-s" | "--stats" => options.stats = true,
+            "-h" | "--help" => options.help = true,
+            "-" => options.input = None,
+            flag if flag.starts_with('-') => return Err(format!("unknown option {flag}")),
+            _ if options.input.is_some() => return Err("more than one input file".to_string()),
+            _ => options.input = Some(arg),
+        }
+    }
+    Ok(options)
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let text = match &options.input {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?,
+        None => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("stdin: {e}"))?;
+            text
+        }
+    };
+    let from = options.from.unwrap_or_else(|| InputFormat::guess(&text));
+    let points = input::parse(&text, from)?;
+    input::check(&points)?;
+    let (t, _) = triangulate(points);
+    if t.triangles().is_empty() {
+        return Err("all points are on a line".to_string());
+    }
+
+    if options.stats {
+        eprintln!("{} points", t.points().len());
+        eprintln!("{}", t.quality_report(10, 5));
+    }
+
+    let content = options.content.unwrap_or(Content::Triangles);
+    let to = options.to.unwrap_or(OutputFormat::Text);
+    let written = match &options.output {
+        Some(path) => {
+            let file = fs::File::create(path).map_err(|e| format!("{path}: {e}"))?;
+            let mut out = BufWriter::new(file);
+            output::write(&mut out, &t, content, to).and_then(|_| out.flush())
+        }
+        None => {
+            let mut out = BufWriter::new(io::stdout().lock());
+            output::write(&mut out, &t, content, to).and_then(|_| out.flush())
+        }
+    };
+    written.map_err(|e| format!("writing output: {e}"))
+}
+
+fn main() -> ExitCode {
+    let result = parse_args(std::env::args().skip(1)).and_then(|options| {
+        if options.help {
+            println!("{USAGE}");
+            Ok(())
+        } else {
+            run(options)
+        }
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("delaunay: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Result<Options, String> {
+        parse_args(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_args() {
+        assert_eq!(
+            args("points.csv -w voronoi --to json -s"),
+            Ok(Options {
+                input: Some("points.csv".to_string()),
+                content: Some(Content::Voronoi),
+                to: Some(OutputFormat::Json),
+                stats: true,
+                ..Options::default()
+            })
+        );
+        assert_eq!(
+            args("-f xml"),
+            Err("unknown input format \"xml\"".to_string())
+        );
+        assert_eq!(args("--output"), Err("--output needs a value".to_string()));
+        assert!(args("a b").is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! Triangles, edges, hull or Voronoi cells as text, CSV or JSON.
+
+use std::io::{self, Write};
+
+use delaunay::{Triangulation, VertIndex, Vertex};
+
+/// What to write about the triangulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Content {
+    /// The three point indices of every triangle.
+    Triangles,
+    /// Every edge once, as its two point indices.
+    Edges,
+    /// The point indices around the convex hull.
+    Hull,
+    /// The corners of each point's Voronoi cell; unbounded cells only have their finite ones.
+    Voronoi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One record per line, fields separated by spaces.
+    Text,
+    /// One record per line after a header.
+    Csv,
+    /// An array of records.
+    Json,
+}
+
+pub fn write(
+    out: &mut impl Write,
+    t: &Triangulation,
+    content: Content,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let index = |v: &VertIndex| v.get().to_string();
+    let (header, records): (&[&str], Vec<Vec<String>>) = match content {
+        Content::Triangles => (
+            &["a", "b", "c"],
+            t.triangles()
+                .chunks_exact(3)
+                .map(|corners| corners.iter().map(index).collect())
+                .collect(),
+        ),
+        Content::Edges => (
+            &["a", "b"],
+            (0..t.triangles().len())
+                .filter(|&e| t.half_edges()[e].is_none_or(|twin| e < twin.get()))
+                .map(|e| {
+                    let next = Triangulation::next_half_edge(e.into());
+                    vec![index(&t.triangles()[e]), index(&t.triangles()[next.get()])]
+                })
+                .collect(),
+        ),
+        Content::Hull => (
+            &["point"],
+            t.hull().iter().map(|v| vec![index(v)]).collect(),
+        ),
+        Content::Voronoi => return write_voronoi(out, t, format),
+    };
+
+    match format {
+        OutputFormat::Text => {
+            for record in records {
+                writeln!(out, "{}", record.join(" "))?;
+            }
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "{}", header.join(","))?;
+            for record in records {
+                writeln!(out, "{}", record.join(","))?;
+            }
+        }
+        OutputFormat::Json => {
+            let records = records
+                .iter()
+                .map(|record| match record.as_slice() {
+                    [single] => single.clone(),
+                    _ => format!("[{}]", record.join(",")),
+                })
+                .collect::<Vec<_>>();
+            writeln!(out, "[{}]", records.join(","))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_voronoi(out: &mut impl Write, t: &Triangulation, format: OutputFormat) -> io::Result<()> {
+    let diagram = t.voronoi();
+    let cells = (0..t.points().len())
+        .map(VertIndex::from)
+        .filter(|&site| !diagram.cell(site).is_empty())
+        .map(|site| {
+            let polygon = diagram.polygon(site).collect::<Vec<Vertex>>();
+            (site, diagram.cell(site).is_bounded(), polygon)
+        });
+
+    match format {
+        OutputFormat::Text => {
+            for (site, _, polygon) in cells {
+                write!(out, "{}", site.get())?;
+                for p in polygon {
+                    write!(out, " {} {}", p.x(), p.y())?;
+                }
+                writeln!(out)?;
+            }
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "site,x,y")?;
+            for (site, _, polygon) in cells {
+                for p in polygon {
+                    writeln!(out, "{},{},{}", site.get(), p.x(), p.y())?;
+                }
+            }
+        }
+        OutputFormat::Json => {
+            let cells = cells
+                .map(|(site, bounded, polygon)| {
+                    let polygon = polygon
+                        .iter()
+                        .map(|p| format!("[{},{}]", json_number(p.x()), json_number(p.y())))
+                        .collect::<Vec<_>>();
+                    format!(
+                        r#"{{"site":{},"bounded":{bounded},"polygon":[{}]}}"#,
+                        site.get(),
+                        polygon.join(",")
+                    )
+                })
+                .collect::<Vec<_>>();
+            writeln!(out, "[{}]", cells.join(","))?;
+        }
+    }
+    Ok(())
+}
+
+// JSON has no infinities or NaN, which circumcenters of flat triangles can have
+fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use delaunay::triangulate;
+
+    fn written(t: &Triangulation, content: Content, format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write(&mut out, t, content, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_pentagon() {
+        // five points around a sixth
+        let (t, _) = triangulate(vec![
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (5.0, 3.0),
+            (2.0, 5.0),
+            (-1.0, 3.0),
+            (2.0, 2.0),
+        ]);
+        let text = written(&t, Content::Triangles, OutputFormat::Text);
+        assert_eq!(text.lines().count(), 5);
+        assert!(text.lines().all(|line| line.split(' ').count() == 3));
+
+        let csv = written(&t, Content::Edges, OutputFormat::Csv);
+        assert_eq!(csv.lines().next(), Some("a,b"));
+        assert_eq!(csv.lines().count(), 1 + 10);
+
+        let hull = written(&t, Content::Hull, OutputFormat::Json);
+        assert_eq!(hull.matches(',').count(), 4);
+
+        // only the inner point has a bounded cell
+        let voronoi = written(&t, Content::Voronoi, OutputFormat::Json);
+        assert_eq!(voronoi.matches(r#""bounded":true"#).count(), 1);
+        assert_eq!(voronoi.matches(r#""bounded":false"#).count(), 5);
+        assert!(voronoi.contains(r#"{"site":5,"bounded":true,"polygon":[["#));
+    }
+
+    #[test]
+    fn test_json_numbers() {
+        assert_eq!(json_number(-2.5), "-2.5");
+        assert_eq!(json_number(f64::NAN), "null");
+        assert_eq!(json_number(f64::NEG_INFINITY), "null");
+    }
+}
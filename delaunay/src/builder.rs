@@ -0,0 +1,361 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::order;
+use crate::types::{VertIndex, Vertex};
+#[cfg(target_has_atomic = "ptr")]
+use crate::CancelToken;
+use crate::{
+    Cancelled, ConstraintError, Dedup, HullContext, Progress, Triangulation, ValidationError,
+    DEFAULT_FLIP_STACK,
+};
+
+/// The order in which [`TriangulationBuilder`] adds points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InsertionOrder {
+    /// The sweep: points by distance from a seed triangle near the center, each joined
+    /// to the hull. The fastest order, and the one [`triangulate`](crate::triangulate) uses.
+    #[default]
+    Radial,
+    /// One point after the other as given, each located by walking from the last.
+    Input,
+    /// Along a Hilbert curve, so each point is located close to the last.
+    Hilbert,
+    /// Along the Z-order curve; cheaper to compute than [`Hilbert`](Self::Hilbert),
+    /// with longer jumps between quadrants.
+    Morton,
+    /// Biased randomized insertion order: random rounds of doubling size, each in Hilbert
+    /// order. Keeps the expected running time of a random order on adversarial or
+    /// clustered input while staying local. The seed makes the order repeatable.
+    Brio(u64),
+}
+
+/// Why [`TriangulationBuilder::build`] failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// A constraint could not be added.
+    Constraint(ConstraintError),
+    /// The finished triangulation did not validate.
+    Invalid(ValidationError),
+    /// The [`CancelToken`] was cancelled before the triangulation was done.
+    Cancelled,
+    /// There were fewer than three points to triangulate.
+    TooFewPoints(usize),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Constraint(error) => write!(f, "bad constraint: {error}"),
+            BuildError::Invalid(error) => write!(f, "invalid triangulation: {error}"),
+            BuildError::Cancelled => write!(f, "{Cancelled}"),
+            BuildError::TooFewPoints(n) => write!(f, "{n} points are too few to triangulate"),
+        }
+    }
+}
+
+impl core::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            BuildError::Constraint(error) => Some(error),
+            BuildError::Invalid(error) => Some(error),
+            BuildError::Cancelled | BuildError::TooFewPoints(_) => None,
+        }
+    }
+}
+
+impl From<ConstraintError> for BuildError {
+    fn from(error: ConstraintError) -> Self {
+        BuildError::Constraint(error)
+    }
+}
+
+impl From<Cancelled> for BuildError {
+    fn from(_: Cancelled) -> Self {
+        BuildError::Cancelled
+    }
+}
+
+impl From<ValidationError> for BuildError {
+    fn from(error: ValidationError) -> Self {
+        BuildError::Invalid(error)
+    }
+}
+
+/// Triangulates points with explicit options; [`triangulate`](crate::triangulate) is
+/// `TriangulationBuilder::new(points).build()`.
+#[derive(Debug)]
+pub struct TriangulationBuilder<'a> {
+    points: Vec<Vertex>,
+    dedup: Dedup,
+    order: InsertionOrder,
+    flip_stack: usize,
+    robust: bool,
+    capacity: usize,
+    constraints: Vec<(VertIndex, VertIndex)>,
+    vertex_index: bool,
+    validate: bool,
+    progress: Progress<'a>,
+}
+
+impl<'a> TriangulationBuilder<'a> {
+    pub fn new(points: Vec<impl Into<Vertex>>) -> Self {
+        Self {
+            points: points.into_iter().map(Into::into).collect(),
+            dedup: Dedup::default(),
+            order: InsertionOrder::default(),
+            flip_stack: DEFAULT_FLIP_STACK,
+            robust: false,
+            capacity: 0,
+            constraints: Vec::new(),
+            vertex_index: false,
+            validate: false,
+            progress: Progress::new(),
+        }
+    }
+
+    /// Which points count as duplicates and are left out.
+    pub fn dedup(mut self, dedup: Dedup) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    pub fn insertion(mut self, order: InsertionOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// How many edges waiting to be checked after a flip fit before the stack grows,
+    /// 256 by default. Only very degenerate input needs more.
+    pub fn flip_stack(mut self, flip_stack: usize) -> Self {
+        self.flip_stack = flip_stack;
+        self
+    }
+
+    /// Uses exact orientation and incircle tests, here and in later updates and
+    /// insertions. They cost little unless points are (nearly) collinear or cocircular.
+    pub fn robust(mut self, robust: bool) -> Self {
+        self.robust = robust;
+        self
+    }
+
+    /// Reserves room for this many points in all, so inserting up to that many later
+    /// does not reallocate.
+    pub fn capacity(mut self, points: usize) -> Self {
+        self.capacity = points;
+        self
+    }
+
+    /// Segments between points, by index, to force into the triangulation,
+    /// see [`Triangulation::add_constraint`].
+    pub fn constraints(mut self, segments: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        self.constraints
+            .extend(segments.into_iter().map(|(a, b)| (a.into(), b.into())));
+        self
+    }
+
+    /// Keeps an edge leaving every point up to date, see [`Triangulation::vertex_edges`].
+    pub fn vertex_index(mut self, vertex_index: bool) -> Self {
+        self.vertex_index = vertex_index;
+        self
+    }
+
+    /// Runs [`Triangulation::validate`] on the result.
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Called with the number of points added so far and the total, see [`Progress`].
+    pub fn progress(mut self, report: impl FnMut(usize, usize) + 'a) -> Self {
+        self.progress = self.progress.report(report);
+        self
+    }
+
+    /// Stops the build with [`BuildError::Cancelled`] once `token` is cancelled.
+    #[cfg(target_has_atomic = "ptr")]
+    pub fn cancel(mut self, token: CancelToken) -> Self {
+        self.progress = self.progress.cancel(token);
+        self
+    }
+
+    pub fn build(mut self) -> Result<(Triangulation, HullContext), BuildError> {
+        let mut triangulation = Triangulation::new(self.points);
+        triangulation.dedup = self.dedup;
+        triangulation.robust = self.robust;
+        triangulation.flip_stack = self.flip_stack;
+        if self.vertex_index {
+            triangulation.vertex_index = Some(Vec::new());
+        }
+
+        let mut hull = triangulation.context();
+        match self.order {
+            InsertionOrder::Radial => {
+                triangulation.update_with_progress(&mut hull, &mut self.progress)?
+            }
+            incremental => {
+                let points = &triangulation.points;
+                let order = match incremental {
+                    InsertionOrder::Hilbert => order::hilbert(points),
+                    InsertionOrder::Morton => order::morton(points),
+                    InsertionOrder::Brio(seed) => order::brio(points, seed),
+                    _ => (0..points.len()).map(VertIndex::from).collect(),
+                };
+                triangulation.update_in_order(&mut hull, &order, &mut self.progress)?;
+            }
+        }
+        for (a, b) in self.constraints {
+            triangulation.add_constraint(&mut hull, a, b)?;
+        }
+
+        let extra = self.capacity.saturating_sub(triangulation.points.len());
+        let half_edges = (2 * self.capacity).saturating_sub(5) * 3;
+        let more_half_edges = half_edges.saturating_sub(triangulation.triangles.len());
+        triangulation.points.reserve(extra);
+        triangulation.duplicate_of.reserve(extra);
+        triangulation.triangles.reserve(more_half_edges);
+        triangulation.half_edges.reserve(more_half_edges);
+        hull.prev.reserve(extra);
+        hull.next.reserve(extra);
+        hull.tri.reserve(extra);
+
+        if self.validate {
+            triangulation.validate()?;
+        }
+        Ok((triangulation, hull))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate, Tolerance};
+
+    fn edge_set(t: &Triangulation) -> Vec<(VertIndex, VertIndex)> {
+        let mut edges = (0..t.triangles().len())
+            .map(|e| {
+                let a = t.triangles()[e];
+                let b = t.triangles()[Triangulation::next_half_edge(e.into())];
+                (a.min(b), a.max(b))
+            })
+            .collect::<Vec<_>>();
+        edges.sort();
+        edges.dedup();
+        edges
+    }
+
+    #[test]
+    fn test_orders_agree() {
+        let mut points = generate::uniform(400, Vertex::ZERO, Vertex::splat(1.0), 11);
+        points.extend(generate::gaussian_clusters(
+            400,
+            &[Vertex::splat(0.3), Vertex::new(0.8, 0.2)],
+            0.02,
+            12,
+        ));
+        let (radial, _) = triangulate(points.clone());
+        for order in [
+            InsertionOrder::Input,
+            InsertionOrder::Hilbert,
+            InsertionOrder::Morton,
+            InsertionOrder::Brio(13),
+        ] {
+            let (t, _) = TriangulationBuilder::new(points.clone())
+                .insertion(order)
+                .robust(true)
+                .validate(true)
+                .build()
+                .unwrap();
+            assert_eq!(edge_set(&t), edge_set(&radial), "{order:?}");
+            assert_eq!(t.hull().len(), radial.hull().len());
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let mut points = generate::jittered_grid(Vertex::ZERO, Vertex::splat(1.0), 10, 10, 0.5, 2);
+        points.push(points[42] + Vertex::new(1e-4, 0.0));
+        let (mut t, mut hull) = TriangulationBuilder::new(points)
+            .dedup(Dedup::new(Tolerance::Absolute(1e-3)))
+            .vertex_index(true)
+            .capacity(200)
+            .validate(true)
+            .build()
+            .unwrap();
+        assert_eq!(t.duplicate_of()[100], Some(42.into()));
+        assert!(t.points.capacity() >= 200 && t.triangles.capacity() >= 3 * 395);
+
+        let check_index = |t: &Triangulation| {
+            let index = t.vertex_edges().unwrap();
+            for (v, e) in index.iter().enumerate() {
+                match e {
+                    Some(e) => assert_eq!(t.triangles()[*e].get(), v),
+                    None => assert!(!t.triangles().contains(&v.into())),
+                }
+            }
+        };
+        check_index(&t);
+        for p in generate::uniform(50, Vertex::splat(-0.5), Vertex::splat(1.5), 3) {
+            t.insert(&mut hull, p);
+        }
+        check_index(&t);
+        assert_eq!(t.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_constraints() {
+        let mut points = generate::uniform(300, Vertex::ZERO, Vertex::splat(1.0), 4);
+        points.extend([(0.05, 0.5), (0.95, 0.5), (0.5, 0.05), (0.5, 0.45)].map(Vertex::from));
+        let (plain, _) = triangulate(points.clone());
+        let (t, _) = TriangulationBuilder::new(points)
+            .constraints([(300, 301), (302, 303)])
+            .validate(true)
+            .build()
+            .unwrap();
+
+        let edges = edge_set(&t);
+        assert!(!edge_set(&plain).contains(&(300.into(), 301.into())));
+        assert!(t.constraints().all(|edge| edges.contains(&edge)));
+        assert!(edges.contains(&(300.into(), 301.into())));
+        assert!(edges.contains(&(302.into(), 303.into())));
+        assert_eq!(t.triangles().len(), plain.triangles().len());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let points = generate::uniform(3000, Vertex::ZERO, Vertex::splat(1.0), 6);
+        for order in [InsertionOrder::Radial, InsertionOrder::Hilbert] {
+            let token = CancelToken::new();
+            let stop = token.clone();
+            let mut reported = 0;
+            let result = TriangulationBuilder::new(points.clone())
+                .insertion(order)
+                .cancel(token)
+                .progress(|k, n| {
+                    assert_eq!(n, 3000);
+                    reported = k;
+                    if k > 0 {
+                        stop.cancel();
+                    }
+                })
+                .build();
+            assert_eq!(result.map(|_| ()), Err(BuildError::Cancelled));
+            assert_eq!(reported, 1024);
+        }
+    }
+
+    #[test]
+    fn test_crossing_constraints() {
+        let square = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.5, 0.2)];
+        let result = TriangulationBuilder::new(square.to_vec())
+            .constraints([(0, 2), (1, 3)])
+            .build();
+        assert_eq!(
+            result.map(|_| ()),
+            Err(BuildError::Constraint(ConstraintError::Crossing(
+                0.into(),
+                2.into()
+            )))
+        );
+    }
+}
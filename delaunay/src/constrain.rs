@@ -0,0 +1,374 @@
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::types::{EdgeIndex, VertIndex, Vertex};
+use crate::{HullContext, Location, Triangulation};
+
+/// Why [`Triangulation::add_constraint`] could not add a segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintError {
+    /// There is no point with this index.
+    MissingPoint(VertIndex),
+    /// The segment crosses the constraint between these two points.
+    Crossing(VertIndex, VertIndex),
+    /// Following the segment between these two points led out of the triangulation,
+    /// which the inexact predicates can do with nearly degenerate points.
+    Lost(VertIndex, VertIndex),
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::MissingPoint(v) => write!(f, "there is no point {}", v.get()),
+            ConstraintError::Crossing(a, b) => {
+                write!(
+                    f,
+                    "the segment crosses the constraint from {} to {}",
+                    a.get(),
+                    b.get()
+                )
+            }
+            ConstraintError::Lost(a, b) => write!(
+                f,
+                "the segment from {} to {} could not be followed through the triangulation",
+                a.get(),
+                b.get()
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ConstraintError {}
+
+impl Triangulation {
+    /// Forces the segment between points `a` and `b` into the triangulation, flipping away
+    /// the edges it crosses, and keeps it through later flips, insertions and updates.
+    ///
+    /// The result is the constrained Delaunay triangulation: every other edge is locally
+    /// Delaunay. A segment running through other points is split at them, and a point
+    /// left out as a duplicate stands for the point it duplicates. Constraints are ignored
+    /// while all points are on a line, and dropped by an update if they cross by then.
+    pub fn add_constraint(
+        &mut self,
+        hull: &mut HullContext,
+        a: VertIndex,
+        b: VertIndex,
+    ) -> Result<(), ConstraintError> {
+        for v in [a, b] {
+            if v.get() >= self.points.len() {
+                return Err(ConstraintError::MissingPoint(v));
+            }
+        }
+        let resolve = |v: VertIndex| self.duplicate_of[v].unwrap_or(v);
+        let (mut a, b) = (resolve(a), resolve(b));
+        if self.triangles.is_empty() {
+            self.constraints.insert(ordered(a, b));
+            return Ok(());
+        }
+        self.sync_hull(hull);
+
+        while a != b {
+            let (reached, crossing) = self.crossings(a, b)?;
+            self.constraints.insert(ordered(a, reached));
+            self.flip_out(hull, a, reached, crossing);
+            a = reached;
+        }
+        Ok(())
+    }
+
+    /// The constrained segments, each as its two points in increasing order.
+    pub fn constraints(&self) -> impl Iterator<Item = (VertIndex, VertIndex)> + '_ {
+        self.constraints.iter().copied()
+    }
+
+    /// Whether edge `e` is a constrained segment.
+    pub fn is_constrained(&self, e: EdgeIndex) -> bool {
+        !self.constraints.is_empty()
+            && self.constraints.contains(&ordered(
+                self.triangles[e],
+                self.triangles[Self::next_half_edge(e)],
+            ))
+    }
+
+    /// Whether each triangle lies in a hole: the region around one of the `holes` points
+    /// up to the constraints and the hull, as Triangle carves the holes of a `.poly` file.
+    /// Hole points outside the hull or on a vertex carve nothing.
+    pub fn in_holes(&self, holes: &[Vertex]) -> Vec<bool> {
+        let mut carved = vec![false; self.triangles.len() / 3];
+        let mut stack = holes
+            .iter()
+            .filter_map(|&p| match self.locate(p) {
+                Location::Triangle(t) => Some(t),
+                Location::Edge(e) => Some(e.get() / 3),
+                Location::Vertex(_) | Location::Outside(_) => None,
+            })
+            .collect::<Vec<_>>();
+        while let Some(t) = stack.pop() {
+            if core::mem::replace(&mut carved[t], true) {
+                continue;
+            }
+            for e in (3 * t..3 * t + 3).map(EdgeIndex::from) {
+                if let Some(twin) = self.half_edges[e].filter(|_| !self.is_constrained(e)) {
+                    stack.push(twin.get() / 3);
+                }
+            }
+        }
+        carved
+    }
+
+    // a point inserted on constrained edge e splits the constraint in two
+    pub(crate) fn split_constraint(&mut self, e: EdgeIndex, i: VertIndex) {
+        let (a, b) = (self.triangles[e], self.triangles[Self::next_half_edge(e)]);
+        if self.constraints.remove(&ordered(a, b)) {
+            self.constraints.insert(ordered(a, i));
+            self.constraints.insert(ordered(i, b));
+        }
+    }
+
+    // adds the constraints of a triangulation that was just rebuilt, dropping those that now cross
+    pub(crate) fn recover_constraints(
+        &mut self,
+        hull: &mut HullContext,
+        constraints: BTreeSet<(VertIndex, VertIndex)>,
+    ) {
+        for (a, b) in constraints {
+            let _ = self.add_constraint(hull, a, b);
+        }
+    }
+
+    // the first point `reached` on the segment from a towards b, and the edges crossing
+    // the segment between a and it, in order
+    fn crossings(
+        &self,
+        a: VertIndex,
+        b: VertIndex,
+    ) -> Result<(VertIndex, Vec<EdgeIndex>), ConstraintError> {
+        let (pa, pb) = (self.points[a], self.points[b]);
+        let ahead = |v: VertIndex| {
+            let (p, d) = (self.points[v] - pa, pb - pa);
+            self.orient(pa, pb, self.points[v]) == 0.0 && p.x() * d.x() + p.y() * d.y() > 0.0
+        };
+
+        // the triangle around a that the segment leaves through
+        let mut first = None;
+        for out in self.star(a) {
+            let u = self.triangles[Self::next_half_edge(out)];
+            let w = self.triangles[Self::prev_half_edge(out)];
+            for v in [u, w] {
+                if v == b || ahead(v) {
+                    return Ok((v, Vec::new()));
+                }
+            }
+            let (pu, pw) = (self.points[u], self.points[w]);
+            if self.orient(pa, pu, pb) > 0.0 && self.orient(pa, pw, pb) < 0.0 {
+                first = Some(Self::next_half_edge(out));
+            }
+        }
+        let lost = ConstraintError::Lost(a, b);
+        let mut e = first.ok_or(lost)?;
+
+        let mut crossing = Vec::new();
+        loop {
+            if self.is_constrained(e) {
+                let (u, v) = ordered(self.triangles[e], self.triangles[Self::next_half_edge(e)]);
+                return Err(ConstraintError::Crossing(u, v));
+            }
+            crossing.push(e);
+            let f = self.half_edges[e].ok_or(lost)?;
+            let v = self.triangles[Self::prev_half_edge(f)];
+            let side = self.orient(pa, pb, self.points[v]);
+            if v == b || side == 0.0 {
+                return Ok((v, crossing));
+            }
+            // leave through whichever other edge has its ends on both sides of the segment
+            let n = Self::next_half_edge(f);
+            let start = self.orient(pa, pb, self.points[self.triangles[n]]);
+            e = if opposite(start, side) {
+                n
+            } else {
+                Self::prev_half_edge(f)
+            };
+        }
+    }
+
+    // flips the edges crossing the segment from a to b out of the way, then makes every
+    // new edge but the segment Delaunay again
+    fn flip_out(
+        &mut self,
+        hull: &mut HullContext,
+        a: VertIndex,
+        b: VertIndex,
+        crossing: Vec<EdgeIndex>,
+    ) {
+        let (pa, pb) = (self.points[a], self.points[b]);
+        let crosses = |t: &Self, e: EdgeIndex| {
+            let (u, v) = (t.triangles[e], t.triangles[Self::next_half_edge(e)]);
+            if [a, b].contains(&u) || [a, b].contains(&v) {
+                return false;
+            }
+            let (pu, pv) = (t.points[u], t.points[v]);
+            let (ou, ov) = (t.orient(pa, pb, pu), t.orient(pa, pb, pv));
+            let (oa, ob) = (t.orient(pu, pv, pa), t.orient(pu, pv, pb));
+            opposite(ou, ov) && opposite(oa, ob)
+        };
+
+        // some quad along the segment is always convex, so this runs out
+        let mut queue = VecDeque::from(crossing);
+        let mut created = Vec::new();
+        while let Some(e) = queue.pop_front() {
+            let Some(twin) = self.half_edges[e] else {
+                continue;
+            };
+            let Some(diagonal) = self.flip_with(hull, e) else {
+                queue.push_back(e);
+                continue;
+            };
+            // the flip moved two edges of the quad into the slots of the old diagonal
+            let moved = |slot: &mut EdgeIndex| {
+                if *slot == Self::prev_half_edge(twin) {
+                    *slot = e;
+                } else if *slot == diagonal {
+                    *slot = twin;
+                }
+            };
+            queue.iter_mut().for_each(moved);
+            created.iter_mut().for_each(moved);
+            if crosses(self, diagonal) {
+                queue.push_back(diagonal);
+            } else {
+                created.push(diagonal);
+            }
+        }
+
+        while let Some(e) = created.pop() {
+            if self.is_locally_delaunay(e) {
+                continue;
+            }
+            let Some(twin) = self.half_edges[e] else {
+                continue;
+            };
+            if self.flip_with(hull, e).is_some() {
+                for slot in [e, twin] {
+                    created.extend([slot, Self::next_half_edge(slot), Self::prev_half_edge(slot)]);
+                }
+            }
+        }
+    }
+
+    // flips e, keeping the hull context pointing at hull edges that moved
+    fn flip_with(&mut self, hull: &mut HullContext, e: EdgeIndex) -> Option<EdgeIndex> {
+        let twin = self.half_edges[e]?;
+        let diagonal = self.flip(e).ok()?;
+        self.move_hull_edge(hull, e.get());
+        self.move_hull_edge(hull, twin.get());
+        Some(diagonal)
+    }
+
+    // the edges leaving point v, turning one way and then the other if that hits the hull
+    fn star(&self, v: VertIndex) -> Vec<EdgeIndex> {
+        let Some(start) = self.edge_from(v) else {
+            return Vec::new();
+        };
+        let mut star = Vec::new();
+        let mut out = start;
+        loop {
+            star.push(out);
+            match self.half_edges[Self::prev_half_edge(out)] {
+                Some(twin) if twin == start => return star,
+                Some(twin) => out = twin,
+                None => break,
+            }
+        }
+        let mut out = start;
+        while let Some(twin) = self.half_edges[out] {
+            out = Self::next_half_edge(twin);
+            star.insert(0, out);
+        }
+        star
+    }
+}
+
+fn ordered(a: VertIndex, b: VertIndex) -> (VertIndex, VertIndex) {
+    (a.min(b), a.max(b))
+}
+
+fn opposite(x: f64, y: f64) -> bool {
+    (x < 0.0 && y > 0.0) || (x > 0.0 && y < 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{triangulate, FlipError, Vertex};
+
+    fn grid() -> Vec<Vertex> {
+        (0..64)
+            .map(|k| Vertex::new((k % 8) as f64, (k / 8) as f64))
+            .collect()
+    }
+
+    #[test]
+    fn test_constraint_through_points() {
+        // the diagonal of a grid runs through every point (k, k)
+        let (mut t, mut hull) = triangulate(grid());
+        let corner = |x: usize, y: usize| VertIndex::from(y * 8 + x);
+        t.add_constraint(&mut hull, corner(0, 1), corner(6, 7))
+            .unwrap();
+        assert_eq!(t.constraints().count(), 6);
+        assert_eq!(t.validate(), Ok(()));
+
+        let crossing = t.add_constraint(&mut hull, corner(0, 6), corner(6, 0));
+        assert!(matches!(crossing, Err(ConstraintError::Crossing(..))));
+        assert_eq!(
+            t.add_constraint(&mut hull, corner(0, 0), 64.into()),
+            Err(ConstraintError::MissingPoint(64.into()))
+        );
+    }
+
+    #[test]
+    fn test_constraints_survive_changes() {
+        let (mut t, mut hull) = triangulate(grid());
+        let (a, b) = (VertIndex::from(2 * 8 + 1), VertIndex::from(3 * 8 + 3));
+        t.add_constraint(&mut hull, a, b).unwrap();
+        let e = (0..t.triangles().len())
+            .map(EdgeIndex::from)
+            .find(|&e| t.is_constrained(e))
+            .unwrap();
+        assert_eq!(t.flip(e), Err(FlipError::Constrained));
+
+        // a point on the segment splits it
+        let i = t.insert(&mut hull, (2.0, 2.5));
+        let expected = vec![(a, i), (b, i)];
+        assert_eq!(t.constraints().collect::<Vec<_>>(), expected);
+        assert_eq!(t.validate(), Ok(()));
+
+        t.update_with(&mut hull);
+        assert_eq!(t.constraints().collect::<Vec<_>>(), expected);
+        assert_eq!(t.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_lost_segment() {
+        // cut the triangulation apart away from the segment's end, as a walk gone wrong
+        // on nearly degenerate points would find it
+        let (mut t, mut hull) = triangulate(grid());
+        let a = VertIndex::from(0);
+        for e in (0..t.triangles.len()).map(EdgeIndex::from) {
+            let ends = [
+                t.triangles[e],
+                t.triangles[Triangulation::next_half_edge(e)],
+            ];
+            if !ends.contains(&a) {
+                t.half_edges[e] = None;
+            }
+        }
+        assert_eq!(
+            t.add_constraint(&mut hull, a, 63.into()),
+            Err(ConstraintError::Lost(a, 63.into()))
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::types::{EdgeIndex, Vertex};
+use crate::Triangulation;
+
+/// A connected piece of the level set `value == level`, the boundary of `value >= level`.
+///
+/// Lines are oriented so that values above the level lie to their left (with y pointing up).
+/// Closed lines do not repeat their first point; open lines start and end on the hull.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Isoline {
+    pub level: f64,
+    pub points: Vec<Vertex>,
+    pub closed: bool,
+}
+
+/// The region where `lower <= value < upper`, as closed rings.
+///
+/// Outer boundaries wind like the triangles and holes the other way,
+/// so the signed areas of the rings add up to the area of the band.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Isoband {
+    pub lower: f64,
+    pub upper: f64,
+    pub rings: Vec<Vec<Vertex>>,
+}
+
+// a corner of an isoband piece: a triangulation vertex, or where a level crosses an edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Node {
+    Vertex(usize),
+    Cut(usize, usize),
+}
+
+impl Triangulation {
+    /// Marching-triangles contours of the piecewise linear field with `values[i]` at point `i`,
+    /// one or more lines per level, in the order of `levels`.
+    pub fn isolines(&self, values: &[f64], levels: &[f64]) -> Vec<Isoline> {
+        assert_eq!(values.len(), self.points.len());
+
+        let mut lines = Vec::new();
+        for &level in levels {
+            // next[k] is the edge where the segment starting on edge k ends
+            let mut next = vec![None; self.triangles.len()];
+            let mut has_prev = vec![false; self.triangles.len()];
+            for t in 0..self.triangles.len() / 3 {
+                let Some((from, to)) = self.crossing(values, level, t) else {
+                    continue;
+                };
+                next[from] = Some(to);
+                has_prev[to] = true;
+            }
+
+            // open lines start on the hull, everything left over is a loop
+            let starts = (0..next.len()).filter(|&k| next[k].is_some() && !has_prev[k]);
+            let starts = starts.collect::<Vec<_>>();
+            for (start, closed) in starts
+                .into_iter()
+                .map(|k| (k, false))
+                .chain((0..next.len()).map(|k| (k, true)))
+            {
+                if next[start].is_none() {
+                    continue;
+                }
+                let mut points = vec![self.cut(values, level, start.into())];
+                let mut k = start;
+                while let Some(n) = next[k].take() {
+                    if n == start {
+                        break;
+                    }
+                    points.push(self.cut(values, level, n.into()));
+                    k = n;
+                }
+                lines.push(Isoline {
+                    level,
+                    points,
+                    closed,
+                });
+            }
+        }
+        lines
+    }
+
+    /// Filled contours: one band between every pair of consecutive `levels`, which must be
+    /// ascending. Bands only cover the triangulation, so an unbounded top or bottom band
+    /// is available by passing an infinite level.
+    pub fn isobands(&self, values: &[f64], levels: &[f64]) -> Vec<Isoband> {
+        assert_eq!(values.len(), self.points.len());
+
+        levels
+            .windows(2)
+            .map(|band| {
+                let (lower, upper) = (band[0], band[1]);
+
+                // directed boundary edges of the band's piece in every triangle;
+                // the edges that pieces share come in opposite pairs and cancel
+                let mut boundary = BTreeMap::new();
+                for t in 0..self.triangles.len() / 3 {
+                    let piece = self.band_piece(values, lower, upper, t);
+                    for (i, &(a, pa)) in piece.iter().enumerate() {
+                        let (b, _) = piece[(i + 1) % piece.len()];
+                        if boundary.remove(&(b, a)).is_none() {
+                            boundary.insert((a, b), pa);
+                        }
+                    }
+                }
+
+                let mut outgoing = BTreeMap::<Node, Vec<(Node, Vertex)>>::new();
+                for ((a, b), pa) in boundary {
+                    outgoing.entry(a).or_default().push((b, pa));
+                }
+                let mut rings = Vec::new();
+                while let Some((&start, _)) = outgoing.first_key_value() {
+                    let mut ring = Vec::new();
+                    let mut node = start;
+                    while let Some(edges) = outgoing.get_mut(&node) {
+                        let (to, p) = edges.pop().unwrap();
+                        if edges.is_empty() {
+                            outgoing.remove(&node);
+                        }
+                        if ring.last() != Some(&p) {
+                            ring.push(p);
+                        }
+                        node = to;
+                        if node == start {
+                            break;
+                        }
+                    }
+                    if ring.len() > 2 {
+                        rings.push(ring);
+                    }
+                }
+
+                Isoband {
+                    lower,
+                    upper,
+                    rings,
+                }
+            })
+            .collect()
+    }
+
+    // the segment of the level set in triangle t, as the undirected keys of the
+    // edges it enters and leaves through
+    fn crossing(&self, values: &[f64], level: f64, t: usize) -> Option<(usize, usize)> {
+        let edges = [3 * t, 3 * t + 1, 3 * t + 2].map(EdgeIndex::from);
+        let above = edges.map(|e| values[self.triangles[e].get()] >= level);
+        if above[0] == above[1] && above[1] == above[2] {
+            return None;
+        }
+
+        // the vertex on its own side of the level, and the edges coming in and going out
+        let lone =
+            (0..3).find(|&i| above[i] != above[(i + 1) % 3] && above[i] != above[(i + 2) % 3])?;
+        let outgoing = self.edge_key(edges[lone]);
+        let incoming = self.edge_key(edges[(lone + 2) % 3]);
+        // triangles wind clockwise with y up, so a lone vertex above is on the left
+        // of the segment from the incoming to the outgoing edge
+        Some(if above[lone] {
+            (incoming, outgoing)
+        } else {
+            (outgoing, incoming)
+        })
+    }
+
+    // the corners of the part of triangle t where lower <= value < upper, in order
+    fn band_piece(&self, values: &[f64], lower: f64, upper: f64, t: usize) -> Vec<(Node, Vertex)> {
+        let mut piece = Vec::new();
+        for e in [3 * t, 3 * t + 1, 3 * t + 2].map(EdgeIndex::from) {
+            let (a, b) = (self.triangles[e], self.triangles[Self::next_half_edge(e)]);
+            let (va, vb) = (values[a], values[b]);
+            if lower <= va && va < upper {
+                piece.push((Node::Vertex(a.get()), self.points[a]));
+            }
+
+            let key = self.edge_key(e);
+            let cuts = [(0, lower), (1, upper)].map(|(i, level)| {
+                ((va < level) != (vb < level))
+                    .then(|| (Node::Cut(key, i), self.cut(values, level, key.into())))
+            });
+            let [first, second] = if va < vb { cuts } else { [cuts[1], cuts[0]] };
+            piece.extend(first);
+            piece.extend(second);
+        }
+        piece
+    }
+
+    // where `level` crosses the undirected edge with key k, computed the same
+    // way from both of its half-edges
+    fn cut(&self, values: &[f64], level: f64, k: EdgeIndex) -> Vertex {
+        let (mut a, mut b) = (self.triangles[k], self.triangles[Self::next_half_edge(k)]);
+        if a.get() > b.get() {
+            (a, b) = (b, a);
+        }
+        let t = (level - values[a]) / (values[b] - values[a]);
+        self.points[a] + (self.points[b] - self.points[a]) * t
+    }
+
+    fn edge_key(&self, e: EdgeIndex) -> usize {
+        self.half_edges[e].map_or(e.get(), |twin| e.get().min(twin.get()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::polygon_area;
+    use crate::{generate, triangulate};
+
+    fn disc() -> (Triangulation, Vec<f64>) {
+        let mut points =
+            generate::poisson_disk_in(&generate::circles(Vertex::ZERO, &[1.0], 64, 0), 0.06, 1);
+        points.extend(generate::circles(Vertex::ZERO, &[1.0], 64, 0));
+        let (t, _) = triangulate(points);
+        let values = t.points().iter().map(|p| p.length_squared()).collect();
+        (t, values)
+    }
+
+    #[test]
+    fn test_linear_field() {
+        let points = generate::jittered_grid(Vertex::ZERO, Vertex::splat(1.0), 10, 10, 0.8, 3);
+        let (t, _) = triangulate(points);
+        let values = t.points().iter().map(|p| p.x()).collect::<Vec<_>>();
+
+        let lines = t.isolines(&values, &[0.5]);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert!(!line.closed);
+        assert!(line.points.iter().all(|p| (p.x() - 0.5).abs() < 1e-12));
+        // larger x lies to the left, so the line runs down
+        assert!(line.points[0].y() > line.points[line.points.len() - 1].y());
+        assert!(line.points.windows(2).all(|w| w[0].y() >= w[1].y()));
+    }
+
+    #[test]
+    fn test_closed_isolines() {
+        let (t, values) = disc();
+        let lines = t.isolines(&values, &[0.04, 0.25, 0.64]);
+        assert_eq!(lines.len(), 3);
+        for line in &lines {
+            assert!(line.closed);
+            let r = line.level.sqrt();
+            assert!(line.points.iter().all(|p| (p.length() - r).abs() < 0.02));
+            // values grow outwards and lie to the left, so the loops run clockwise
+            let area = polygon_area(&line.points);
+            assert!(area < 0.0);
+            assert!((-area - core::f64::consts::PI * r * r).abs() < 0.05 * r);
+        }
+    }
+
+    #[test]
+    fn test_isobands() {
+        let (t, values) = disc();
+        let levels = [f64::NEG_INFINITY, 0.25, 0.5, f64::INFINITY];
+        let bands = t.isobands(&values, &levels);
+        assert_eq!(bands.len(), 3);
+
+        let area = |band: &Isoband| {
+            band.rings
+                .iter()
+                .map(|r| polygon_area(r))
+                .sum::<f64>()
+                .abs()
+        };
+        let total = bands.iter().map(area).sum::<f64>();
+        let hull = t.hull().iter().map(|&i| t.points()[i]).collect::<Vec<_>>();
+        assert!((total - polygon_area(&hull).abs()).abs() < 1e-9);
+
+        // a disc, an annulus with one hole, and the rest of the hull
+        assert_eq!(bands[0].rings.len(), 1);
+        assert_eq!(bands[1].rings.len(), 2);
+        let signs = bands[1].rings.iter().map(|r| polygon_area(r) < 0.0);
+        assert_eq!(signs.filter(|&clockwise| clockwise).count(), 1);
+        let pi = core::f64::consts::PI;
+        assert!((area(&bands[0]) - pi * 0.25).abs() < 0.01);
+        assert!((area(&bands[1]) - pi * 0.25).abs() < 0.01);
+    }
+}
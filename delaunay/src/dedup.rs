@@ -0,0 +1,254 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::math;
+use crate::types::{VertIndex, Vertex};
+use crate::Triangulation;
+
+/// How close two points must be to count as duplicates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// A distance; zero only merges points at exactly the same position.
+    Absolute(f64),
+    /// A fraction of the diagonal of the points' bounding box.
+    Relative(f64),
+}
+
+/// How a triangulation treats points that lie within a [`Tolerance`] of an earlier point.
+///
+/// The later point is left out of the triangulation and recorded in
+/// [`Triangulation::duplicate_of`]. By default only points closer than
+/// `sqrt(2 * f64::EPSILON)` count as duplicates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dedup {
+    tolerance: Tolerance,
+    merge: bool,
+}
+
+impl Default for Dedup {
+    fn default() -> Self {
+        Self {
+            tolerance: Tolerance::Absolute(math::sqrt(2.0 * f64::EPSILON)),
+            merge: false,
+        }
+    }
+}
+
+impl Dedup {
+    pub fn new(tolerance: Tolerance) -> Self {
+        Self {
+            tolerance,
+            ..Self::default()
+        }
+    }
+
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Moves every group of duplicates, the kept point included, to the group's centroid,
+    /// instead of keeping the first point where it is.
+    pub fn merge(mut self, merge: bool) -> Self {
+        self.merge = merge;
+        self
+    }
+
+    // whether the sweep can find the duplicates on its own: with the default tolerance
+    // they end up next to each other in its order, which breaks ties by position
+    pub(crate) fn in_sweep(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // the earlier point each point duplicates, if any
+    pub(crate) fn find(&self, points: &[Vertex]) -> Vec<Option<VertIndex>> {
+        let tolerance = match self.tolerance {
+            Tolerance::Absolute(distance) => distance,
+            Tolerance::Relative(fraction) => {
+                let (min, max) = points.iter().filter(|p| !p.is_nan()).fold(
+                    (Vertex::INFINITY, Vertex::INFINITY * -1.0),
+                    |(min, max), &p| (min.min(p), max.max(p)),
+                );
+                fraction * (max - min).length()
+            }
+        };
+
+        // grid cells counted from the corner of the bounding box, so they stay small
+        // for points far from the origin
+        let origin = points
+            .iter()
+            .filter(|p| !p.is_nan())
+            .fold(Vertex::INFINITY, |min, &p| min.min(p));
+        let cell = |p: Vertex| {
+            if tolerance > 0.0 {
+                let cell = (p - origin) / tolerance;
+                (math::floor(cell.x()) as i64, math::floor(cell.y()) as i64)
+            } else {
+                // -0.0 and 0.0 are the same position
+                let bits = |v: f64| (v + 0.0).to_bits() as i64;
+                (bits(p.x()), bits(p.y()))
+            }
+        };
+        let reach = if tolerance > 0.0 { 1 } else { 0 };
+
+        // the kept points in each cell, in order; kept points are farther apart than the
+        // tolerance, so a cell holds only a few of them
+        let mut kept = BTreeMap::<(i64, i64), Vec<usize>>::new();
+        let mut duplicate_of = vec![None; points.len()];
+        for (i, &p) in points.iter().enumerate() {
+            if p.is_nan() {
+                continue;
+            }
+            let (x, y) = cell(p);
+            let neighbors = (-reach..=reach)
+                .flat_map(|dx| (-reach..=reach).map(move |dy| (dx, dy)))
+                .filter_map(|(dx, dy)| Some((x.checked_add(dx)?, y.checked_add(dy)?)));
+            let original = neighbors
+                .filter_map(|key| kept.get(&key))
+                .filter_map(|cell| {
+                    cell.iter()
+                        .copied()
+                        .find(|&j| (points[j] - p).length() <= tolerance)
+                })
+                .min();
+            match original {
+                Some(j) => duplicate_of[i] = Some(VertIndex::from(j)),
+                None => kept.entry((x, y)).or_default().push(i),
+            }
+        }
+        duplicate_of
+    }
+}
+
+impl Triangulation {
+    /// The duplicate handling used by the next update.
+    pub fn set_dedup(&mut self, dedup: Dedup) {
+        self.dedup = dedup;
+    }
+
+    /// For every point, the point it was found to duplicate and that stands in for it
+    /// in the triangulation; `None` for the points that were triangulated.
+    ///
+    /// Points the sweep had to drop because they were too close to the hull to
+    /// triangulate map to the hull point they ran into.
+    pub fn duplicate_of(&self) -> &[Option<VertIndex>] {
+        &self.duplicate_of
+    }
+
+    // fills in duplicate_of, merging groups of duplicates if asked to; a sweep finds
+    // the duplicates itself where it can
+    pub(crate) fn find_duplicates(&mut self, sweep: bool) {
+        if sweep && self.dedup.in_sweep() {
+            self.duplicate_of = vec![None; self.points.len()];
+            return;
+        }
+        self.duplicate_of = self.dedup.find(&self.points);
+        if !self.dedup.merge {
+            return;
+        }
+
+        let mut groups = BTreeMap::<VertIndex, (Vertex, usize)>::new();
+        for (i, original) in self.duplicate_of.iter().enumerate() {
+            if let Some(j) = *original {
+                let group = groups.entry(j).or_insert((self.points[j], 1));
+                *group = (group.0 + self.points[i], group.1 + 1);
+            }
+        }
+        for (j, (sum, count)) in groups {
+            let centroid = sum / count as f64;
+            self.points[j] = centroid;
+        }
+        for i in 0..self.points.len() {
+            if let Some(j) = self.duplicate_of[i] {
+                self.points[i] = self.points[j];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate};
+
+    fn used(t: &Triangulation) -> Vec<bool> {
+        let mut used = vec![false; t.points().len()];
+        for &v in t.triangles() {
+            used[v] = true;
+        }
+        used
+    }
+
+    #[test]
+    fn test_duplicate_map() {
+        let mut points = generate::uniform(100, Vertex::ZERO, Vertex::splat(10.0), 1);
+        points.push(points[3]);
+        points.push(points[7] + Vertex::new(1e-3, 0.0));
+        points.push(points[7] - Vertex::new(0.0, 1e-3));
+
+        let (mut t, mut hull) = triangulate(points.clone());
+        assert_eq!(t.duplicate_of()[100], Some(3.into()));
+        assert_eq!(t.duplicate_of()[101..], [None, None]);
+
+        t.set_dedup(Dedup::new(Tolerance::Absolute(0.01)));
+        t.update_with(&mut hull);
+        assert_eq!(t.duplicate_of()[..100], [None; 100]);
+        assert_eq!(t.duplicate_of()[100..], [3, 7, 7].map(|v| Some(v.into())));
+        let used = used(&t);
+        assert!((0..103).all(|i| used[i] == t.duplicate_of()[i].is_none()));
+        assert_eq!(t.points(), points);
+
+        // the same, relative to the 10 x 10 box
+        t.set_dedup(Dedup::new(Tolerance::Relative(0.01 / 200f64.sqrt())));
+        t.update_with(&mut hull);
+        assert_eq!(t.duplicate_of()[100..], [3, 7, 7].map(|v| Some(v.into())));
+    }
+
+    #[test]
+    fn test_repeated_point() {
+        // every copy is checked against the one kept point, not against all earlier copies
+        let points = vec![Vertex::new(0.5, 0.5); 40_000];
+        let duplicate_of = Dedup::new(Tolerance::Absolute(1e-6)).find(&points);
+        assert_eq!(duplicate_of[0], None);
+        assert!(duplicate_of[1..].iter().all(|&v| v == Some(0.into())));
+    }
+
+    #[test]
+    fn test_merge() {
+        let corners = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        let mut points = corners.map(Vertex::from).to_vec();
+        points.extend([(0.5, 0.5), (0.52, 0.5), (0.5, 0.53), (1.0, 0.01)].map(Vertex::from));
+
+        let (mut t, mut hull) = triangulate(points);
+        t.set_dedup(Dedup::new(Tolerance::Absolute(0.05)).merge(true));
+        t.update_with(&mut hull);
+        let expected = [None, None, None, None, None, Some(4), Some(4), Some(1)];
+        assert_eq!(t.duplicate_of(), expected.map(|v| v.map(VertIndex::from)));
+
+        let centroid = Vertex::new(1.52 / 3.0, 1.53 / 3.0);
+        assert!((4..7).all(|i| t.points()[i] == centroid));
+        assert_eq!(t.points()[1], Vertex::new(1.0, 0.005));
+        assert_eq!(t.triangles().len(), 4 * 3);
+
+        // merging again leaves everything in place
+        let before = t.points().to_vec();
+        t.update_with(&mut hull);
+        assert_eq!(t.points(), before);
+    }
+
+    #[test]
+    fn test_far_points() {
+        let (t, _) = triangulate(vec![(0.0, 0.0), (1e15, 0.0), (0.0, 1e15), (1e15, 1e15)]);
+        assert_eq!(t.duplicate_of(), [None; 4]);
+        assert_eq!(t.triangles().len(), 2 * 3);
+
+        // cells count from the bounding box, so they don't overflow far from the origin
+        let mut points = generate::uniform(50, Vertex::splat(1e12), Vertex::splat(2e12), 3);
+        points.push(points[5] + Vertex::new(1e-9, 0.0));
+        points.push(Vertex::new(-1e300, 1e300));
+        let duplicate_of = Dedup::new(Tolerance::Absolute(1e-6)).find(&points);
+        assert_eq!(duplicate_of[50], Some(5.into()));
+        assert_eq!(duplicate_of.iter().flatten().count(), 1);
+    }
+}
@@ -0,0 +1,150 @@
+use alloc::vec::Vec;
+
+use crate::math;
+use crate::types::{EdgeIndex, VertIndex, Vertex};
+use crate::util::{circumcenter, clip_polygon};
+use crate::Triangulation;
+
+impl Triangulation {
+    /// The Voronoi diagram, with a vertex at the circumcenter of every triangle.
+    pub fn voronoi(&self) -> Diagram {
+        let centers = self
+            .triangles
+            .chunks_exact(3)
+            .map(|t| circumcenter(self.points[t[0]], self.points[t[1]], self.points[t[2]]))
+            .collect();
+        Diagram::new(self, centers)
+    }
+}
+
+/// The dual of a triangulation: one vertex per triangle and one cell per site.
+///
+/// With circumcenters as the triangle centers this is the Voronoi diagram,
+/// with power centers of a regular triangulation it is the power diagram.
+#[derive(Debug, Clone)]
+pub struct Diagram {
+    vertices: Vec<Vertex>,
+    cells: Vec<Cell>,
+}
+
+/// The region of a [`Diagram`] belonging to a single site.
+///
+/// Sites on the convex hull have unbounded cells, which are closed by two rays:
+/// one running from infinity into the first vertex and one leaving the last.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cell {
+    vertices: Vec<usize>,
+    rays: Option<(Vertex, Vertex)>,
+}
+
+impl Diagram {
+    pub(crate) fn new(triangulation: &Triangulation, vertices: Vec<Vertex>) -> Self {
+        let points = triangulation.points();
+        let triangles = triangulation.triangles();
+        let half_edges = triangulation.half_edges();
+
+        let cells = triangulation
+            .inedges()
+            .into_iter()
+            .map(|start| {
+                let Some(start) = start else {
+                    return Cell::default();
+                };
+
+                let mut cell = Cell::default();
+                let mut last = start;
+                for e in triangulation.edges_around_point(start) {
+                    cell.vertices.push(e.get() / 3);
+                    last = e;
+                }
+
+                if half_edges[start].is_none() {
+                    // the walk starts on the incoming hull edge and ends on the outgoing one
+                    let outgoing = Triangulation::next_half_edge(last);
+                    let normal = |e: EdgeIndex| {
+                        let a = points[triangles[e]];
+                        let b = points[triangles[Triangulation::next_half_edge(e)]];
+                        Vertex::new(a.y() - b.y(), b.x() - a.x())
+                    };
+                    cell.rays = Some((normal(start), normal(outgoing)));
+                }
+                cell
+            })
+            .collect();
+
+        Self { vertices, cells }
+    }
+
+    /// The diagram vertices, one per triangle of the primal triangulation.
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    /// The cells, indexed like the points of the primal triangulation.
+    /// Points that are not part of the triangulation have an empty cell.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    pub fn cell(&self, site: VertIndex) -> &Cell {
+        &self.cells[site]
+    }
+
+    /// The corners of a cell in order. Unbounded cells only list their finite corners.
+    pub fn polygon(&self, site: VertIndex) -> impl Iterator<Item = Vertex> + '_ {
+        self.cells[site].vertices.iter().map(|&v| self.vertices[v])
+    }
+
+    /// The cell of `site` clipped to a convex `region`, unbounded cells included.
+    /// Empty when the cell misses the region or the site has no cell.
+    pub fn clip(&self, site: VertIndex, region: &[Vertex]) -> Vec<Vertex> {
+        let mut polygon = self.polygon(site).collect::<Vec<_>>();
+        if polygon.is_empty() || region.is_empty() {
+            return Vec::new();
+        }
+
+        if let Some((r0, r1)) = self.cells[site].rays {
+            // close the cell with points far enough out to lie beyond the region
+            let center = region.iter().fold(Vertex::ZERO, |c, &v| c + v) / region.len() as f64;
+            let reach = math::sqrt(
+                region
+                    .iter()
+                    .chain(&polygon)
+                    .map(|v| v.distance_squared(center))
+                    .fold(0.0, f64::max),
+            );
+            let far = 4.0 * reach + 1.0;
+
+            let (r0, r1) = (r0 / r0.length(), r1 / r1.length());
+            let (first, last) = (polygon[0], polygon[polygon.len() - 1]);
+            let outward = r0 + r1;
+            polygon.push(last + r1 * far);
+            if outward.length() > 0.0 {
+                polygon.push((first + last) / 2.0 + outward * (2.0 * far / outward.length()));
+            }
+            polygon.push(first + r0 * far);
+        }
+
+        clip_polygon(&polygon, region)
+    }
+}
+
+impl Cell {
+    /// Indices into [`Diagram::vertices`], ordered around the site.
+    pub fn vertices(&self) -> &[usize] {
+        &self.vertices
+    }
+
+    /// Directions of the rays closing an unbounded cell, as `(incoming, outgoing)`.
+    pub fn rays(&self) -> Option<(Vertex, Vertex)> {
+        self.rays
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.rays.is_none()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+}
@@ -0,0 +1,222 @@
+use core::fmt;
+
+use crate::types::EdgeIndex;
+use crate::Triangulation;
+
+/// Why [`Triangulation::flip`] refused an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlipError {
+    /// The edge lies on the convex hull and has no triangle on its other side.
+    HullEdge,
+    /// The two triangles sharing the edge form a non-convex (or degenerate) quad,
+    /// so the flipped edge would leave it.
+    NotConvex,
+    /// The edge is a constraint and has to stay.
+    Constrained,
+}
+
+impl fmt::Display for FlipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlipError::HullEdge => write!(f, "cannot flip a hull edge"),
+            FlipError::NotConvex => write!(f, "cannot flip the diagonal of a non-convex quad"),
+            FlipError::Constrained => write!(f, "cannot flip a constrained edge"),
+        }
+    }
+}
+
+impl core::error::Error for FlipError {}
+
+impl Triangulation {
+    /// Replaces edge `e` by the other diagonal of the quad formed by its two triangles.
+    ///
+    /// Both triangles keep their slots. Returns the half-edge of the new diagonal in
+    /// `e`'s triangle, running from the vertex opposite `e` to the vertex opposite its twin.
+    pub fn flip(&mut self, e: EdgeIndex) -> Result<EdgeIndex, FlipError> {
+        let Some(twin) = self.half_edges[e] else {
+            return Err(FlipError::HullEdge);
+        };
+        if self.is_constrained(e) {
+            return Err(FlipError::Constrained);
+        }
+
+        let p0 = self.points[self.triangles[Self::prev_half_edge(e)]];
+        let pr = self.points[self.triangles[e]];
+        let pl = self.points[self.triangles[Self::next_half_edge(e)]];
+        let p1 = self.points[self.triangles[Self::prev_half_edge(twin)]];
+        if self.orient(p1, pl, p0) <= 0.0 || self.orient(p0, pr, p1) <= 0.0 {
+            return Err(FlipError::NotConvex);
+        }
+
+        self.swap_diagonal(e.get(), twin);
+        // a hull edge may have moved into either slot; the hull context catches up on its
+        // next use
+        self.hull_moved |= self.half_edges[e].is_none() || self.half_edges[twin].is_none();
+        Ok(Self::prev_half_edge(e))
+    }
+
+    /// Whether the vertex across edge `e` lies outside the circumcircle of `e`'s triangle.
+    /// Hull edges and constrained edges are always locally Delaunay.
+    pub fn is_locally_delaunay(&self, e: EdgeIndex) -> bool {
+        let Some(twin) = self.half_edges[e] else {
+            return true;
+        };
+        if self.is_constrained(e) {
+            return true;
+        }
+        let p0 = self.triangles[Self::prev_half_edge(e)];
+        let pr = self.triangles[e];
+        let pl = self.triangles[Self::next_half_edge(e)];
+        let p1 = self.triangles[Self::prev_half_edge(twin)];
+        !self.in_circumcircle(
+            self.points[p0],
+            self.points[pr],
+            self.points[pl],
+            self.points[p1],
+        )
+    }
+
+    /*           pl                    pl
+     *          /||\                  /  \
+     *       al/ || \bl            al/    \a
+     *        /  ||  \              /      \
+     *       /  a||b  \    flip    /___ar___\
+     *     p0\   ||   /p1   =>   p0\---bl---/p1
+     *        \  ||  /              \      /
+     *       ar\ || /br             b\    /br
+     *          \||/                  \  /
+     *           pr                    pr
+     */
+    pub(crate) fn swap_diagonal(&mut self, a: usize, b: EdgeIndex) {
+        let ar = Self::prev_half_edge(a.into());
+        let bl = Self::prev_half_edge(b);
+        let p0 = self.triangles[ar];
+        let p1 = self.triangles[bl];
+
+        self.triangles[a] = p1;
+        self.triangles[b] = p0;
+
+        self.link(a, self.half_edges[bl]);
+        self.link(b.get(), self.half_edges[ar]);
+        self.link(ar.get(), Some(bl));
+
+        if let Some(index) = &mut self.vertex_index {
+            // the ends of the old diagonal lost the slots they started at
+            let (al, br) = (Self::next_half_edge(a.into()), Self::next_half_edge(b));
+            index[self.triangles[al]] = Some(al);
+            index[self.triangles[br]] = Some(br);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::types::Vertex;
+    use crate::util::orient2d_fast;
+    use crate::{generate, triangulate};
+
+    fn edge_set(t: &Triangulation) -> Vec<(usize, usize)> {
+        let mut edges = (0..t.triangles().len())
+            .map(|e| {
+                let a = t.triangles()[e].get();
+                let b = t.triangles()[Triangulation::next_half_edge(e.into())].get();
+                (a.min(b), a.max(b))
+            })
+            .collect::<Vec<_>>();
+        edges.sort();
+        edges.dedup();
+        edges
+    }
+
+    fn assert_consistent(t: &Triangulation) {
+        for (e, twin) in t.half_edges().iter().enumerate() {
+            if let Some(twin) = *twin {
+                assert_eq!(t.half_edges()[twin], Some(e.into()));
+                assert_eq!(
+                    t.triangles()[e],
+                    t.triangles()[Triangulation::next_half_edge(twin)]
+                );
+            }
+        }
+        for tri in t.triangles().chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| t.points()[tri[i]]);
+            assert!(orient2d_fast(a, b, c) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_flip_round_trip() {
+        let (mut t, _) = triangulate(generate::uniform(50, Vertex::ZERO, Vertex::splat(1.0), 2));
+        let edges = edge_set(&t);
+
+        let mut flipped = 0;
+        for e in 0..t.triangles().len() {
+            let e = EdgeIndex::from(e);
+            let Ok(f) = t.flip(e) else {
+                continue;
+            };
+            flipped += 1;
+            assert_consistent(&t);
+            assert!(!t.is_locally_delaunay(f));
+            assert_ne!(edge_set(&t), edges);
+
+            // the new diagonal joins the vertices that were opposite the old one
+            let g = t.flip(f).unwrap();
+            assert_consistent(&t);
+            assert!(t.is_locally_delaunay(g));
+            assert_eq!(edge_set(&t), edges);
+        }
+        assert!(flipped > 50);
+    }
+
+    #[test]
+    fn test_flip_errors() {
+        // a point inside a triangle: every interior edge is the diagonal of a non-convex quad
+        let (mut t, _) = triangulate(vec![
+            Vertex::new(0.0, 0.0),
+            Vertex::new(2.0, 1.0),
+            Vertex::new(0.0, 2.0),
+            Vertex::new(0.5, 1.0),
+        ]);
+        for e in 0..t.triangles().len() {
+            let e = EdgeIndex::from(e);
+            let result = t.flip(e);
+            if t.half_edges()[e].is_none() {
+                assert_eq!(result, Err(FlipError::HullEdge));
+            } else {
+                assert_eq!(result, Err(FlipError::NotConvex));
+            }
+        }
+    }
+
+    #[test]
+    fn test_flip_next_to_hull() {
+        let (mut t, mut hull) =
+            triangulate(generate::uniform(60, Vertex::ZERO, Vertex::splat(1.0), 5));
+        // flip the other edges of triangles on the hull and back again, which keeps the
+        // edges but moves hull edges between slots
+        let mut flipped = 0;
+        for e in 0..t.triangles().len() {
+            let e = EdgeIndex::from(e);
+            let next = Triangulation::next_half_edge(e);
+            let prev = Triangulation::prev_half_edge(e);
+            if t.half_edges()[next].is_none() || t.half_edges()[prev].is_none() {
+                if let Ok(f) = t.flip(e) {
+                    t.flip(f).unwrap();
+                    flipped += 1;
+                }
+            }
+        }
+        assert!(flipped > 0);
+
+        for k in 0..5 {
+            let x = k as f64 / 4.0;
+            t.insert(&mut hull, Vertex::new(x, -0.5 - x));
+        }
+        assert_eq!(t.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,395 @@
+//! Deterministic point-set generators for tests, benchmarks and demos.
+//!
+//! Every generator takes a `seed`; the same arguments always produce the same points.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::TAU;
+
+use crate::math;
+use crate::types::Vertex;
+use crate::util::point_in_polygon;
+
+/// A small SplitMix64 generator, good enough for sampling and free of dependencies.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub(crate) fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.next_f64()
+    }
+
+    /// Standard normal, by the Box-Muller transform.
+    pub(crate) fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.next_f64();
+        let v = self.next_f64();
+        math::sqrt(-2.0 * math::ln(u)) * math::cos(TAU * v)
+    }
+}
+
+fn lerp(min: Vertex, max: Vertex, u: f64, v: f64) -> Vertex {
+    Vertex::new(
+        min.x() + (max.x() - min.x()) * u,
+        min.y() + (max.y() - min.y()) * v,
+    )
+}
+
+/// `n` points drawn uniformly from the rectangle spanned by `min` and `max`.
+pub fn uniform(n: usize, min: Vertex, max: Vertex, seed: u64) -> Vec<Vertex> {
+    let mut rng = Rng::new(seed);
+    (0..n)
+        .map(|_| {
+            let u = rng.next_f64();
+            lerp(min, max, u, rng.next_f64())
+        })
+        .collect()
+}
+
+/// Bridson's Poisson-disk sampling of the rectangle spanned by `min` and `max`:
+/// no two points are closer than `radius`, and no gap can fit another point.
+pub fn poisson_disk(min: Vertex, max: Vertex, radius: f64, seed: u64) -> Vec<Vertex> {
+    let inside =
+        |p: Vertex| p.x() >= min.x() && p.x() < max.x() && p.y() >= min.y() && p.y() < max.y();
+    bridson(min, max, radius, seed, inside)
+}
+
+/// Poisson-disk sampling restricted to a simple polygon of either winding.
+pub fn poisson_disk_in(polygon: &[Vertex], radius: f64, seed: u64) -> Vec<Vertex> {
+    let Some(&first) = polygon.first() else {
+        return Vec::new();
+    };
+    let (min, max) = polygon
+        .iter()
+        .fold((first, first), |(lo, hi), &p| (lo.min(p), hi.max(p)));
+    bridson(min, max, radius, seed, |p| point_in_polygon(p, polygon))
+}
+
+fn bridson(
+    min: Vertex,
+    max: Vertex,
+    radius: f64,
+    seed: u64,
+    inside: impl Fn(Vertex) -> bool,
+) -> Vec<Vertex> {
+    const ATTEMPTS: usize = 30;
+
+    let mut rng = Rng::new(seed);
+    let mut points = Vec::new();
+    // also rejects NaN
+    let valid = radius > 0.0 && max.x() > min.x() && max.y() > min.y();
+    if !valid {
+        return points;
+    }
+
+    // a background grid with at most one sample per cell
+    let cell = radius / core::f64::consts::SQRT_2;
+    let width = math::ceil((max.x() - min.x()) / cell) as usize + 1;
+    let height = math::ceil((max.y() - min.y()) / cell) as usize + 1;
+    let mut grid: Vec<Option<usize>> = vec![None; width * height];
+    let key = |p: Vertex| {
+        let i = ((p.x() - min.x()) / cell) as usize;
+        let j = ((p.y() - min.y()) / cell) as usize;
+        (i.min(width - 1), j.min(height - 1))
+    };
+
+    let fits = |p: Vertex, points: &[Vertex], grid: &[Option<usize>]| {
+        if !inside(p) {
+            return false;
+        }
+        let (i, j) = key(p);
+        for y in j.saturating_sub(2)..(j + 3).min(height) {
+            for x in i.saturating_sub(2)..(i + 3).min(width) {
+                if let Some(k) = grid[y * width + x] {
+                    if points[k].distance_squared(p) < radius * radius {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    };
+
+    // the first sample, by rejection from the bounding box
+    let mut active = Vec::new();
+    for _ in 0..ATTEMPTS * ATTEMPTS {
+        let u = rng.next_f64();
+        let p = lerp(min, max, u, rng.next_f64());
+        if inside(p) {
+            let (i, j) = key(p);
+            grid[j * width + i] = Some(0);
+            points.push(p);
+            active.push(0);
+            break;
+        }
+    }
+
+    while !active.is_empty() {
+        let slot = (rng.next_u64() % active.len() as u64) as usize;
+        let center = points[active[slot]];
+        let mut found = false;
+        for _ in 0..ATTEMPTS {
+            // uniform in the annulus between radius and twice the radius
+            let angle = TAU * rng.next_f64();
+            let r = radius * math::sqrt(1.0 + 3.0 * rng.next_f64());
+            let p = center + Vertex::new(math::cos(angle), math::sin(angle)) * r;
+            if fits(p, &points, &grid) {
+                let (i, j) = key(p);
+                grid[j * width + i] = Some(points.len());
+                active.push(points.len());
+                points.push(p);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            active.swap_remove(slot);
+        }
+    }
+    points
+}
+
+/// One point per cell of an `nx` by `ny` grid over the rectangle spanned by `min` and `max`,
+/// displaced from the cell center by up to `jitter` times half the cell size.
+/// A `jitter` of zero gives a regular grid, one gives stratified sampling.
+pub fn jittered_grid(
+    min: Vertex,
+    max: Vertex,
+    nx: usize,
+    ny: usize,
+    jitter: f64,
+    seed: u64,
+) -> Vec<Vertex> {
+    let mut rng = Rng::new(seed);
+    let mut points = Vec::with_capacity(nx * ny);
+    for j in 0..ny {
+        for i in 0..nx {
+            let du = jitter * rng.range(-0.5, 0.5);
+            let dv = jitter * rng.range(-0.5, 0.5);
+            let u = (i as f64 + 0.5 + du) / nx as f64;
+            let v = (j as f64 + 0.5 + dv) / ny as f64;
+            points.push(lerp(min, max, u, v));
+        }
+    }
+    points
+}
+
+fn radical_inverse(mut i: u64, base: u64) -> f64 {
+    let inv = 1.0 / base as f64;
+    let (mut f, mut r) = (inv, 0.0);
+    while i > 0 {
+        r += f * (i % base) as f64;
+        i /= base;
+        f *= inv;
+    }
+    r
+}
+
+/// The first `n` points of the Halton sequence in bases 2 and 3, scaled to the
+/// rectangle spanned by `min` and `max`. The seed applies a random toroidal shift
+/// (Cranley-Patterson rotation), which keeps the discrepancy low.
+pub fn halton(n: usize, min: Vertex, max: Vertex, seed: u64) -> Vec<Vertex> {
+    let mut rng = Rng::new(seed);
+    let (su, sv) = (rng.next_f64(), rng.next_f64());
+    (1..=n as u64)
+        .map(|i| {
+            let u = math::fract(radical_inverse(i, 2) + su);
+            let v = math::fract(radical_inverse(i, 3) + sv);
+            lerp(min, max, u, v)
+        })
+        .collect()
+}
+
+/// The first `n` points of the two-dimensional Sobol sequence, scaled to the rectangle
+/// spanned by `min` and `max`. The seed applies a random digital shift, which
+/// preserves the net structure of the sequence.
+pub fn sobol(n: usize, min: Vertex, max: Vertex, seed: u64) -> Vec<Vertex> {
+    const BITS: usize = 32;
+
+    // the first dimension is the van der Corput sequence, the second comes from
+    // the primitive polynomial x + 1, whose direction numbers obey m_k = 2 m_(k-1) ^ m_(k-1)
+    let mut m = 1u32;
+    let directions: [[u32; BITS]; 2] = [
+        core::array::from_fn(|k| 1 << (BITS - 1 - k)),
+        core::array::from_fn(|k| {
+            let d = m << (BITS - 1 - k);
+            m ^= m << 1;
+            d
+        }),
+    ];
+
+    let mut rng = Rng::new(seed);
+    let mut x = [rng.next_u64() as u32, rng.next_u64() as u32];
+    let scale = 1.0 / (1u64 << BITS) as f64;
+    let mut points = Vec::with_capacity(n);
+    for i in 0..n {
+        points.push(lerp(min, max, x[0] as f64 * scale, x[1] as f64 * scale));
+        // Gray code order: flip the direction number of the lowest zero bit of i
+        let c = (!i).trailing_zeros() as usize;
+        if c < BITS {
+            x[0] ^= directions[0][c];
+            x[1] ^= directions[1][c];
+        }
+    }
+    points
+}
+
+/// `n` points split evenly between normal distributions with standard deviation
+/// `sigma` around each of the `centers`.
+pub fn gaussian_clusters(n: usize, centers: &[Vertex], sigma: f64, seed: u64) -> Vec<Vertex> {
+    let mut rng = Rng::new(seed);
+    if centers.is_empty() {
+        return Vec::new();
+    }
+    (0..n)
+        .map(|i| {
+            let c = centers[i % centers.len()];
+            let dx = rng.normal();
+            c + Vertex::new(dx, rng.normal()) * sigma
+        })
+        .collect()
+}
+
+/// `n` points evenly spaced on each of the concentric circles with the given `radii`.
+/// The seed rotates every circle by a random phase.
+///
+/// All points on a circle are cocircular, the worst case for the in-circle test.
+pub fn circles(center: Vertex, radii: &[f64], n: usize, seed: u64) -> Vec<Vertex> {
+    let mut rng = Rng::new(seed);
+    let mut points = Vec::with_capacity(radii.len() * n);
+    for &r in radii {
+        let phase = TAU * rng.next_f64();
+        for k in 0..n {
+            let angle = phase + TAU * k as f64 / n as f64;
+            points.push(center + Vertex::new(math::cos(angle), math::sin(angle)) * r);
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: Vertex = Vertex::ZERO;
+    const MAX: Vertex = Vertex::new(2.0, 1.0);
+
+    fn in_rect(p: &Vertex) -> bool {
+        (0.0..=2.0).contains(&p.x()) && (0.0..=1.0).contains(&p.y())
+    }
+
+    #[test]
+    fn test_deterministic() {
+        assert_eq!(uniform(100, MIN, MAX, 7), uniform(100, MIN, MAX, 7));
+        assert_ne!(uniform(100, MIN, MAX, 7), uniform(100, MIN, MAX, 8));
+        assert_eq!(
+            poisson_disk(MIN, MAX, 0.1, 3),
+            poisson_disk(MIN, MAX, 0.1, 3)
+        );
+        assert_ne!(
+            poisson_disk(MIN, MAX, 0.1, 3),
+            poisson_disk(MIN, MAX, 0.1, 4)
+        );
+        assert_eq!(halton(50, MIN, MAX, 1), halton(50, MIN, MAX, 1));
+        assert_eq!(sobol(50, MIN, MAX, 1), sobol(50, MIN, MAX, 1));
+    }
+
+    #[test]
+    fn test_poisson_disk() {
+        let radius = 0.05;
+        let points = poisson_disk(MIN, MAX, radius, 1);
+        assert!(points.iter().all(in_rect));
+        for (i, p) in points.iter().enumerate() {
+            for q in &points[i + 1..] {
+                assert!(p.distance_squared(*q) >= radius * radius);
+            }
+        }
+        // maximal: every probe point lies within 2r of a sample
+        for q in uniform(500, MIN, MAX, 2) {
+            assert!(points
+                .iter()
+                .any(|p| p.distance_squared(q) < 4.0 * radius * radius));
+        }
+        // a maximal packing of a 2 x 1 box with r = 0.05 holds several hundred points
+        assert!(points.len() > 400, "{}", points.len());
+    }
+
+    #[test]
+    fn test_poisson_disk_in_polygon() {
+        let triangle = [
+            Vertex::new(0.0, 0.0),
+            Vertex::new(1.0, 0.0),
+            Vertex::new(0.0, 1.0),
+        ];
+        let points = poisson_disk_in(&triangle, 0.05, 9);
+        assert!(points.len() > 50);
+        assert!(points.iter().all(|p| point_in_polygon(*p, &triangle)));
+    }
+
+    #[test]
+    fn test_jittered_grid() {
+        let points = jittered_grid(MIN, MAX, 8, 4, 0.0, 0);
+        assert_eq!(points.len(), 32);
+        assert_eq!(points[0], Vertex::new(0.125, 0.125));
+        assert_eq!(points[31], Vertex::new(1.875, 0.875));
+
+        // every jittered point stays in its own cell
+        let points = jittered_grid(MIN, MAX, 8, 4, 1.0, 5);
+        for (k, p) in points.iter().enumerate() {
+            assert_eq!((p.x() * 4.0) as usize, k % 8);
+            assert_eq!((p.y() * 4.0) as usize, k / 8);
+        }
+    }
+
+    #[test]
+    fn test_low_discrepancy() {
+        // each of the 16 cells of a 4 x 4 grid receives exactly 4 of the first 64
+        // unshifted Sobol points, and close to 4 of the shifted sequences
+        for (points, slack) in [
+            (sobol(64, Vertex::ZERO, Vertex::splat(1.0), 11), 0),
+            (halton(64, Vertex::ZERO, Vertex::splat(1.0), 11), 3),
+        ] {
+            let mut counts = [0usize; 16];
+            for p in &points {
+                counts[(p.y() * 4.0) as usize * 4 + (p.x() * 4.0) as usize] += 1;
+            }
+            assert!(counts.iter().all(|&c| c.abs_diff(4) <= slack), "{counts:?}");
+        }
+        assert!(sobol(100, MIN, MAX, 0).iter().all(in_rect));
+        assert!(halton(100, MIN, MAX, 0).iter().all(in_rect));
+    }
+
+    #[test]
+    fn test_clusters_and_circles() {
+        let centers = [Vertex::new(-5.0, 0.0), Vertex::new(5.0, 0.0)];
+        let points = gaussian_clusters(200, &centers, 0.5, 4);
+        let left = points.iter().filter(|p| p.x() < 0.0).count();
+        assert_eq!(left, 100);
+        assert!(points
+            .iter()
+            .all(|p| centers.iter().any(|&c| p.distance_squared(c) < 9.0)));
+
+        let points = circles(Vertex::splat(1.0), &[1.0, 2.0], 12, 0);
+        assert_eq!(points.len(), 24);
+        for (k, p) in points.iter().enumerate() {
+            let r = if k < 12 { 1.0 } else { 2.0 };
+            assert!((p.distance_squared(Vertex::splat(1.0)).sqrt() - r).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use alloc::vec::Vec;
+
+use crate::generate::Rng;
+use crate::types::{VertIndex, Vertex};
+use crate::util::{circumcenter, convex_hull_of, orient2d_fast, polygon_area, polygon_centroid};
+use crate::Triangulation;
+
+/// The convex hull of `points` without triangulating them, as indices in the same winding
+/// as [`Triangulation::hull`]. Duplicate and collinear boundary points are left out.
+pub fn convex_hull(points: &[impl Into<Vertex> + Copy]) -> Vec<VertIndex> {
+    let points = points.iter().map(|&p| p.into()).collect::<Vec<_>>();
+    let ids = (0..points.len()).collect();
+    convex_hull_of(&points, ids, false)
+        .into_iter()
+        .map(VertIndex::from)
+        .collect()
+}
+
+/// A rectangle that may be rotated, see [`Triangulation::min_area_rectangle`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangle {
+    /// The corners in the winding of the hull, starting with one end of a hull edge.
+    pub corners: [Vertex; 4],
+    /// The side running along the hull edge.
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rectangle {
+    pub fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        2.0 * (self.width + self.height)
+    }
+}
+
+/// A circle, see [`Triangulation::min_enclosing_circle`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Vertex,
+    pub radius: f64,
+}
+
+impl Circle {
+    /// Whether `p` lies inside the circle or within a relative `1e-9` of its boundary.
+    pub fn contains(&self, p: Vertex) -> bool {
+        (p - self.center).length() <= self.radius * (1.0 + 1e-9)
+    }
+
+    fn through_two(a: Vertex, b: Vertex) -> Self {
+        let center = (a + b) / 2.0;
+        Self {
+            center,
+            radius: (a - center).length(),
+        }
+    }
+
+    fn through_three(a: Vertex, b: Vertex, c: Vertex) -> Self {
+        if orient2d_fast(a, b, c) == 0.0 {
+            // collinear, so the circle over the two outermost points covers the third
+            return [(a, b), (b, c), (a, c)]
+                .map(|(p, q)| Self::through_two(p, q))
+                .into_iter()
+                .max_by(|p, q| p.radius.total_cmp(&q.radius))
+                .unwrap();
+        }
+        let center = circumcenter(a, b, c);
+        Self {
+            center,
+            radius: (a - center).length(),
+        }
+    }
+}
+
+fn dot(a: Vertex, b: Vertex) -> f64 {
+    a.x() * b.x() + a.y() * b.y()
+}
+
+fn cross(a: Vertex, b: Vertex) -> f64 {
+    a.x() * b.y() - a.y() * b.x()
+}
+
+// the distance from p to the segment a-b
+fn segment_distance(p: Vertex, a: Vertex, b: Vertex) -> f64 {
+    let d = b - a;
+    let t = if d == Vertex::ZERO {
+        0.0
+    } else {
+        (dot(p - a, d) / d.length_squared()).clamp(0.0, 1.0)
+    };
+    (p - (a + d * t)).length()
+}
+
+impl Triangulation {
+    pub(crate) fn hull_points(&self) -> Vec<Vertex> {
+        self.hull.iter().map(|&i| self.points[i]).collect()
+    }
+
+    /// The area enclosed by the convex hull.
+    pub fn hull_area(&self) -> f64 {
+        polygon_area(&self.hull_points()).abs()
+    }
+
+    /// The length of the convex hull's boundary.
+    pub fn hull_perimeter(&self) -> f64 {
+        let hull = self.hull_points();
+        let n = hull.len();
+        (0..n).map(|i| (hull[(i + 1) % n] - hull[i]).length()).sum()
+    }
+
+    /// The center of mass of the area enclosed by the convex hull. Falls back to the mean
+    /// of the hull points when all of them are collinear.
+    pub fn hull_centroid(&self) -> Vertex {
+        let hull = self.hull_points();
+        if polygon_area(&hull) == 0.0 {
+            return hull.iter().fold(Vertex::ZERO, |sum, &p| sum + p) / hull.len() as f64;
+        }
+        polygon_centroid(&hull)
+    }
+
+    /// Whether `p` lies inside the convex hull or on its boundary.
+    pub fn hull_contains(&self, p: Vertex) -> bool {
+        let hull = self.hull_points();
+        if hull.len() < 3 {
+            return self.hull_distance(p) == 0.0;
+        }
+        // the hull winds like the triangles, with its interior to the right of every edge
+        let n = hull.len();
+        (0..n).all(|i| orient2d_fast(hull[i], hull[(i + 1) % n], p) >= 0.0)
+    }
+
+    /// The distance from `p` to the area enclosed by the convex hull, zero for points inside.
+    pub fn hull_distance(&self, p: Vertex) -> f64 {
+        let hull = self.hull_points();
+        let n = hull.len();
+        if n >= 3 && self.hull_contains(p) {
+            return 0.0;
+        }
+        (0..n)
+            .map(|i| segment_distance(p, hull[i], hull[(i + 1) % n]))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// The two hull points farthest apart, found with rotating calipers.
+    pub fn hull_diameter(&self) -> Option<(VertIndex, VertIndex)> {
+        let hull = self.hull_points();
+        let n = hull.len();
+        if n < 2 {
+            return None;
+        }
+
+        let mut best = (0, 1, (hull[1] - hull[0]).length_squared());
+        let mut j = 1;
+        for i in 0..n {
+            let (a, b) = (hull[i], hull[(i + 1) % n]);
+            // advance the antipodal point while it moves away from the edge
+            let height = |k: usize| cross(b - a, hull[k % n] - a).abs();
+            j = j.max(i + 1);
+            while j < i + n && height(j + 1) > height(j) {
+                j += 1;
+            }
+            for (u, v) in [(i, j % n), ((i + 1) % n, j % n)] {
+                let d = (hull[u] - hull[v]).length_squared();
+                if d > best.2 {
+                    best = (u, v, d);
+                }
+            }
+        }
+        Some((self.hull[best.0], self.hull[best.1]))
+    }
+
+    /// The smallest-area rectangle around the points. One of its sides lies on a hull edge,
+    /// so rotating calipers find it in linear time.
+    pub fn min_area_rectangle(&self) -> Option<Rectangle> {
+        self.min_rectangle(Rectangle::area)
+    }
+
+    /// The rectangle around the points with the shortest perimeter, see
+    /// [`min_area_rectangle`](Self::min_area_rectangle).
+    pub fn min_perimeter_rectangle(&self) -> Option<Rectangle> {
+        self.min_rectangle(Rectangle::perimeter)
+    }
+
+    fn min_rectangle(&self, measure: fn(&Rectangle) -> f64) -> Option<Rectangle> {
+        let hull = self.hull_points();
+        let n = hull.len();
+        match n {
+            0 => return None,
+            1 => {
+                return Some(Rectangle {
+                    corners: [hull[0]; 4],
+                    width: 0.0,
+                    height: 0.0,
+                })
+            }
+            _ => {}
+        }
+
+        // going around the hull from an edge, the points first move furthest along the edge,
+        // then furthest from it, then furthest back; all three only ever move forward
+        let (mut j, mut k, mut l) = (1, 1, 1);
+        let mut best: Option<Rectangle> = None;
+        for i in 0..n {
+            let a = hull[i];
+            let d = hull[(i + 1) % n] - a;
+            let d = d / d.length();
+            let along = |m: usize| dot(hull[m % n] - a, d);
+            let away = |m: usize| cross(d, hull[m % n] - a).abs();
+
+            j = j.max(i + 1);
+            while j < i + n && along(j + 1) > along(j) {
+                j += 1;
+            }
+            k = k.max(j);
+            while k < i + n && away(k + 1) > away(k) {
+                k += 1;
+            }
+            l = l.max(k);
+            while l < i + n && along(l + 1) < along(l) {
+                l += 1;
+            }
+
+            let normal = Vertex::new(-d.y(), d.x());
+            let normal = if dot(hull[k % n] - a, normal) < 0.0 {
+                normal * -1.0
+            } else {
+                normal
+            };
+            let (low, high, height) = (along(l), along(j), away(k));
+            let mut corners = [
+                a + d * low,
+                a + d * high,
+                a + d * high + normal * height,
+                a + d * low + normal * height,
+            ];
+            if polygon_area(&corners) > 0.0 {
+                corners.reverse();
+            }
+            let rectangle = Rectangle {
+                corners,
+                width: high - low,
+                height,
+            };
+            if best.is_none_or(|best| measure(&rectangle) < measure(&best)) {
+                best = Some(rectangle);
+            }
+        }
+        best
+    }
+
+    /// The smallest circle containing every point, by Welzl's algorithm over the hull.
+    pub fn min_enclosing_circle(&self) -> Option<Circle> {
+        let mut hull = self.hull_points();
+        if hull.is_empty() {
+            return None;
+        }
+
+        // a fixed shuffle keeps the expected running time linear and the result repeatable
+        let mut rng = Rng::new(hull.len() as u64);
+        for i in (1..hull.len()).rev() {
+            hull.swap(i, rng.next_u64() as usize % (i + 1));
+        }
+
+        let mut circle = Circle::through_two(hull[0], hull[0]);
+        for i in 1..hull.len() {
+            if circle.contains(hull[i]) {
+                continue;
+            }
+            circle = Circle::through_two(hull[0], hull[i]);
+            for j in 0..i {
+                if circle.contains(hull[j]) {
+                    continue;
+                }
+                circle = Circle::through_two(hull[i], hull[j]);
+                for k in 0..j {
+                    if !circle.contains(hull[k]) {
+                        circle = Circle::through_three(hull[i], hull[j], hull[k]);
+                    }
+                }
+            }
+        }
+        Some(circle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate};
+
+    fn square() -> Triangulation {
+        // a unit square turned by 30 degrees, with points inside
+        let (sin, cos) = 30f64.to_radians().sin_cos();
+        let mut points = generate::uniform(50, Vertex::splat(0.1), Vertex::splat(0.9), 2);
+        points.extend([(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(Vertex::from));
+        let points = points
+            .into_iter()
+            .map(|p| Vertex::new(p.x() * cos - p.y() * sin, p.x() * sin + p.y() * cos))
+            .collect::<Vec<_>>();
+        triangulate(points).0
+    }
+
+    #[test]
+    fn test_measures() {
+        let t = square();
+        assert!((t.hull_area() - 1.0).abs() < 1e-12);
+        assert!((t.hull_perimeter() - 4.0).abs() < 1e-12);
+        let centroid = (t.points()[50] + t.points()[52]) / 2.0;
+        assert!((t.hull_centroid() - centroid).length() < 1e-12);
+
+        assert!(t.hull_contains(centroid));
+        assert!(t.hull_contains(t.points()[51]));
+        assert!(!t.hull_contains(t.points()[51] * 1.01));
+        assert_eq!(t.hull_distance(centroid), 0.0);
+        let outside = t.points()[50] + (t.points()[50] - t.points()[51]);
+        assert!((t.hull_distance(outside) - 1.0).abs() < 1e-12);
+
+        let (a, b) = t.hull_diameter().unwrap();
+        let ends = [a.min(b).get(), a.max(b).get()];
+        assert!(ends == [50, 52] || ends == [51, 53]);
+    }
+
+    #[test]
+    fn test_enclosing_shapes() {
+        let t = square();
+        for rectangle in [t.min_area_rectangle(), t.min_perimeter_rectangle()] {
+            let rectangle = rectangle.unwrap();
+            assert!((rectangle.width - 1.0).abs() < 1e-12);
+            assert!((rectangle.height - 1.0).abs() < 1e-12);
+            assert!(polygon_area(&rectangle.corners) < 0.0);
+        }
+
+        let circle = t.min_enclosing_circle().unwrap();
+        assert!((circle.radius - 0.5f64.sqrt()).abs() < 1e-12);
+        assert!(t.points().iter().all(|&p| circle.contains(p)));
+    }
+
+    #[test]
+    fn test_random_hulls() {
+        for seed in 0..5 {
+            let points = generate::gaussian_clusters(200, &[Vertex::ZERO], 1.0, seed);
+            let (t, _) = triangulate(points.clone());
+            // the same cycle, perhaps starting elsewhere
+            let mut hull = convex_hull(&points);
+            let start = hull.iter().position(|&i| i == t.hull()[0]).unwrap();
+            hull.rotate_left(start);
+            assert_eq!(hull, t.hull());
+
+            // the calipers agree with trying every pair and every edge
+            let (a, b) = t.hull_diameter().unwrap();
+            let diameter = (t.points()[a] - t.points()[b]).length();
+            let farthest = points
+                .iter()
+                .flat_map(|&p| points.iter().map(move |&q| (p - q).length()))
+                .fold(0.0, f64::max);
+            assert_eq!(diameter, farthest);
+
+            let area = t.min_area_rectangle().unwrap().area();
+            let hull = t.hull_points();
+            let n = hull.len();
+            let brute = (0..n)
+                .map(|i| {
+                    let d = hull[(i + 1) % n] - hull[i];
+                    let d = d / d.length();
+                    let along = hull.iter().map(|&p| dot(p - hull[i], d));
+                    let away = hull.iter().map(|&p| cross(d, p - hull[i]).abs());
+                    let (low, high) = along.fold((f64::INFINITY, f64::NEG_INFINITY), |r, x| {
+                        (r.0.min(x), r.1.max(x))
+                    });
+                    (high - low) * away.fold(0.0, f64::max)
+                })
+                .fold(f64::INFINITY, f64::min);
+            assert!((area - brute).abs() < 1e-9 * brute);
+
+            let circle = t.min_enclosing_circle().unwrap();
+            assert!(points.iter().all(|&p| circle.contains(p)));
+            assert!(circle.radius <= diameter / 3f64.sqrt() + 1e-12);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::locate::Location;
+use crate::types::{EdgeIndex, VertIndex, Vertex};
+use crate::util::circumcenter;
+use crate::{Cancelled, HullContext, Progress, Triangulation};
+
+impl Triangulation {
+    /// Adds a point to an up-to-date triangulation, splitting the triangle or edge it
+    /// falls on, or joining it to the hull edges it sees from outside, and flipping
+    /// edges until the triangulation is Delaunay again.
+    ///
+    /// Returns the index of the new point, or of the existing point at the same position.
+    pub fn insert(&mut self, hull: &mut HullContext, p: impl Into<Vertex>) -> VertIndex {
+        let p = p.into();
+        let location = self.locate(p);
+        self.insert_at(hull, p, location).0
+    }
+
+    // inserts p at a known location; also returns a triangle that has p as a corner
+    pub(crate) fn insert_at(
+        &mut self,
+        hull: &mut HullContext,
+        p: Vertex,
+        location: Location,
+    ) -> (VertIndex, usize) {
+        let i = VertIndex::from(self.points.len());
+        if let Location::Vertex(v) = location {
+            return (v, self.edge_from(v).map_or(0, |e| e.get() / 3));
+        }
+        if self.triangles.is_empty() {
+            // no triangle to grow from yet
+            self.points.push(p);
+            *hull = self.context();
+            self.update_with(hull);
+            let e = self.triangles.iter().position(|&u| u == i).unwrap_or(0);
+            return (i, e / 3);
+        }
+        self.sync_hull(hull);
+        self.add_point(hull, p);
+        (i, self.insert_point(hull, i, location))
+    }
+
+    // triangulates the stored point i at its location, returning a triangle that has it
+    // as a corner; a point on a vertex is recorded as a duplicate of that vertex
+    pub(crate) fn insert_point(
+        &mut self,
+        hull: &mut HullContext,
+        i: VertIndex,
+        location: Location,
+    ) -> usize {
+        match location {
+            Location::Vertex(v) => {
+                self.duplicate_of[i] = Some(v);
+                self.edge_from(v).map_or(0, |e| e.get() / 3)
+            }
+            Location::Outside(e) => self.split_outside(hull, e, i),
+            Location::Triangle(t) => {
+                self.split_triangle(hull, t, i);
+                t
+            }
+            Location::Edge(e) => {
+                self.split_constraint(e, i);
+                match self.half_edges[e] {
+                    Some(twin) => self.split_edge(hull, e, twin, i),
+                    None => self.split_hull_edge(hull, e, i),
+                }
+                e.get() / 3
+            }
+        }
+    }
+
+    // triangulates the points one by one in the given order, each located from the last,
+    // starting from the first three that are not on a line
+    pub(crate) fn update_in_order(
+        &mut self,
+        hull: &mut HullContext,
+        order: &[VertIndex],
+        progress: &mut Progress,
+    ) -> Result<(), Cancelled> {
+        self.reserve();
+        self.find_duplicates(false);
+        let kept = order
+            .iter()
+            .copied()
+            .filter(|&i| self.duplicate_of[i].is_none())
+            .collect::<Vec<_>>();
+
+        let seed = kept.first().and_then(|&a| {
+            let pa = self.points[a];
+            let b = kept.iter().copied().find(|&b| self.points[b] != pa)?;
+            let pb = self.points[b];
+            let c = kept
+                .iter()
+                .copied()
+                .find(|&c| self.orient(pa, pb, self.points[c]) != 0.0)?;
+            Some(if self.orient(pa, pb, self.points[c]) > 0.0 {
+                [a, b, c]
+            } else {
+                [a, c, b]
+            })
+        });
+        let Some(seed) = seed else {
+            // all on a line
+            return self.update_with_progress(hull, progress);
+        };
+
+        let constraints = core::mem::take(&mut self.constraints);
+        if self.vertex_index.is_some() {
+            self.vertex_index = Some(vec![None; self.points.len()]);
+        }
+        self.triangles.clear();
+        self.half_edges.clear();
+        self.hull_moved = false;
+        let [a, b, c] = seed.map(|v| self.points[v]);
+        hull.seed(
+            (a, b, c),
+            (seed[0], seed[1], seed[2]),
+            circumcenter(a, b, c),
+        );
+        self.push_triangle(seed, [None; 3]);
+        self.hull = seed.to_vec();
+
+        let mut hint = 0;
+        let total = self.points.len();
+        for (k, i) in kept.into_iter().enumerate() {
+            if progress.check(k, total).is_err() {
+                self.constraints = constraints;
+                return Err(self.abandon());
+            }
+            if seed.contains(&i) {
+                continue;
+            }
+            let location = self.locate_from(self.points[i], hint);
+            hint = self.insert_point(hull, i, location);
+        }
+        self.recover_constraints(hull, constraints);
+        progress.finish(total);
+        Ok(())
+    }
+
+    fn add_point(&mut self, hull: &mut HullContext, p: Vertex) {
+        self.points.push(p);
+        self.duplicate_of.push(None);
+        if let Some(index) = &mut self.vertex_index {
+            index.push(None);
+        }
+        hull.prev.push(VertIndex::default());
+        hull.next.push(VertIndex::default());
+        hull.tri.push(EdgeIndex::default());
+    }
+
+    fn push_triangle(&mut self, vertices: [VertIndex; 3], twins: [Option<EdgeIndex>; 3]) -> usize {
+        let t = self.triangles.len();
+        self.triangles.extend(vertices);
+        self.half_edges.extend([None; 3]);
+        for (k, twin) in twins.into_iter().enumerate() {
+            self.link(t + k, twin);
+        }
+        if let Some(index) = &mut self.vertex_index {
+            for (k, v) in vertices.into_iter().enumerate() {
+                index[v] = Some((t + k).into());
+            }
+        }
+        t
+    }
+
+    // points the hull context at every hull edge again after flips that moved them
+    // without it, see `restore_delaunay`
+    pub(crate) fn sync_hull(&mut self, hull: &mut HullContext) {
+        if !core::mem::take(&mut self.hull_moved) {
+            return;
+        }
+        for e in 0..self.triangles.len() {
+            self.move_hull_edge(hull, e);
+        }
+    }
+
+    // keeps the hull pointing at a hull edge that moved into slot e
+    pub(crate) fn move_hull_edge(&self, hull: &mut HullContext, e: usize) {
+        if self.half_edges[e].is_none() {
+            hull.tri[self.triangles[e]] = e.into();
+        }
+    }
+
+    /*           a                    a
+     *          / \                  /|\
+     *         /   \                / | \
+     *        /     \     =>       /  i  \
+     *       /       \            / /   \ \
+     *      c---------b          c---------b
+     */
+    fn split_triangle(&mut self, hull: &mut HullContext, t: usize, i: VertIndex) {
+        let t0 = 3 * t;
+        let [a, b, c] = [0, 1, 2].map(|k| self.triangles[t0 + k]);
+        let [_, hb, hc] = [0, 1, 2].map(|k| self.half_edges[t0 + k]);
+
+        // (a, b, i) keeps the slot, (b, c, i) and (c, a, i) are new
+        self.triangles[t0 + 2] = i;
+        let n = self.push_triangle([b, c, i], [hb, None, Some((t0 + 1).into())]);
+        let m = self.push_triangle([c, a, i], [hc, Some((t0 + 2).into()), Some((n + 1).into())]);
+        self.move_hull_edge(hull, n);
+        self.move_hull_edge(hull, m);
+
+        self.legalize(hull, t0, &mut ());
+        self.legalize(hull, n, &mut ());
+        self.legalize(hull, m, &mut ());
+    }
+
+    // splits the edge e from a to b, with twin f, into four triangles around i
+    fn split_edge(&mut self, hull: &mut HullContext, e: EdgeIndex, f: EdgeIndex, i: VertIndex) {
+        let (en, fn_) = (Self::next_half_edge(e), Self::next_half_edge(f));
+        let (a, b) = (self.triangles[e], self.triangles[f]);
+        let c = self.triangles[Self::prev_half_edge(e)];
+        let d = self.triangles[Self::prev_half_edge(f)];
+        let (h_bc, h_ad) = (self.half_edges[en], self.half_edges[fn_]);
+
+        // (a, i, c) and (b, i, d) keep their slots, (i, b, c) and (i, a, d) are new
+        self.triangles[en] = i;
+        self.triangles[fn_] = i;
+        let n = self.push_triangle([i, b, c], [Some(f), h_bc, Some(en)]);
+        let m = self.push_triangle([i, a, d], [Some(e), h_ad, Some(fn_)]);
+        self.move_hull_edge(hull, n + 1);
+        self.move_hull_edge(hull, m + 1);
+
+        self.legalize(hull, Self::prev_half_edge(e).get(), &mut ());
+        self.legalize(hull, n + 1, &mut ());
+        self.legalize(hull, Self::prev_half_edge(f).get(), &mut ());
+        self.legalize(hull, m + 1, &mut ());
+    }
+
+    // splits the hull edge e from a to b, keeping i on the hull between them
+    fn split_hull_edge(&mut self, hull: &mut HullContext, e: EdgeIndex, i: VertIndex) {
+        let en = Self::next_half_edge(e);
+        let (a, b) = (self.triangles[e], self.triangles[en]);
+        let c = self.triangles[Self::prev_half_edge(e)];
+        let h_bc = self.half_edges[en];
+
+        // (a, i, c) keeps the slot, (i, b, c) is new
+        self.triangles[en] = i;
+        let n = self.push_triangle([i, b, c], [None, h_bc, Some(en)]);
+        self.move_hull_edge(hull, n + 1);
+
+        hull.next[a] = i;
+        hull.prev[i] = a;
+        hull.next[i] = b;
+        hull.prev[b] = i;
+        hull.tri[a] = e;
+        hull.tri[i] = n.into();
+        hull.size += 1;
+        let k = self.hull.iter().position(|&v| v == a).map_or(0, |k| k + 1);
+        self.hull.insert(k, i);
+
+        self.legalize(hull, Self::prev_half_edge(e).get(), &mut ());
+        self.legalize(hull, n + 1, &mut ());
+    }
+
+    // joins i to hull edge e, which it sees from outside, and to every other hull edge
+    // it sees, walking the hull like the sweep does; returns the first new triangle
+    fn split_outside(&mut self, hull: &mut HullContext, e: EdgeIndex, i: VertIndex) -> usize {
+        let p = self.points[i];
+        let sees =
+            |t: &Self, a: VertIndex, b: VertIndex| t.orient(p, t.points[a], t.points[b]) < 0.0;
+
+        // back up to the first hull edge that i sees
+        let mut e = self.triangles[e];
+        for _ in 0..hull.size {
+            if !sees(self, hull.prev[e], e) {
+                break;
+            }
+            e = hull.prev[e];
+        }
+
+        let first = self.push_triangle([e, i, hull.next[e]], [None, None, Some(hull.tri[e])]);
+        hull.tri[i] = self.legalize(hull, first + 2, &mut ());
+        hull.tri[e] = first.into();
+        hull.size += 1;
+
+        let mut n = hull.next[e];
+        let mut q = hull.next[n];
+        while sees(self, n, q) {
+            let t = self.push_triangle([n, i, q], [Some(hull.tri[i]), None, Some(hull.tri[n])]);
+            hull.tri[i] = self.legalize(hull, t + 2, &mut ());
+            hull.next[n] = n;
+            hull.size -= 1;
+            n = q;
+            q = hull.next[n];
+        }
+
+        hull.start = e;
+        hull.prev[i] = e;
+        hull.next[e] = i;
+        hull.prev[n] = i;
+        hull.next[i] = n;
+
+        self.hull.clear();
+        let mut v = hull.start;
+        for _ in 0..hull.size {
+            self.hull.push(v);
+            v = hull.next[v];
+        }
+        first / 3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate};
+
+    fn assert_delaunay(t: &Triangulation) {
+        for (e, twin) in t.half_edges().iter().enumerate() {
+            if let Some(twin) = *twin {
+                assert_eq!(t.half_edges()[twin], Some(e.into()));
+            }
+            assert!(t.is_locally_delaunay(e.into()));
+        }
+        let boundary = t.half_edges().iter().filter(|h| h.is_none()).count();
+        assert_eq!(boundary, t.hull().len());
+    }
+
+    #[test]
+    fn test_insert_matches_triangulate() {
+        let points = generate::uniform(300, Vertex::new(0.1, 0.1), Vertex::splat(0.9), 6);
+        let corners = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        let (mut t, mut hull) = triangulate(corners.to_vec());
+
+        for (k, &p) in points.iter().enumerate() {
+            assert_eq!(t.insert(&mut hull, p), VertIndex::from(k + 4));
+        }
+        assert_delaunay(&t);
+
+        let (all, _) = triangulate([corners.map(Vertex::from).to_vec(), points].concat());
+        assert_eq!(t.triangles().len(), all.triangles().len());
+    }
+
+    #[test]
+    fn test_insert_on_edges() {
+        // a grid fills in row by row, hitting edges, hull edges and existing vertices
+        let corners = [(0.0, 0.0), (8.0, 0.0), (8.0, 8.0), (0.0, 8.0)];
+        let (mut t, mut hull) = triangulate(corners.to_vec());
+        for y in 0..=8 {
+            for x in 0..=8 {
+                t.insert(&mut hull, (x as f64, y as f64));
+            }
+        }
+        assert_eq!(t.points().len(), 81);
+        assert_eq!(t.hull().len(), 32);
+        assert_eq!(t.triangles().len() / 3, 2 * 64);
+        assert_delaunay(&t);
+        assert_eq!(
+            t.insert(&mut hull, (3.0, 5.0)),
+            VertIndex::from(4 + 5 * 9 + 3 - 2)
+        );
+    }
+
+    #[test]
+    fn test_insert_outside() {
+        let (mut t, mut hull) = triangulate(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+        assert_eq!(t.insert(&mut hull, (2.0, 2.0)), VertIndex::from(3));
+        assert_eq!(t.insert(&mut hull, (0.5, 0.5)), VertIndex::from(4));
+        assert_eq!(t.triangles().len() / 3, 4);
+        assert_delaunay(&t);
+    }
+}
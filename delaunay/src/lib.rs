@@ -1,15 +1,88 @@
+//! Delaunay triangulation and the structures built on it.
+//!
+//! The crate is `no_std` with `alloc`. The default `std` feature takes the float math
+//! from std; without it, enable `libm` instead.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!(
+    "the delaunay crate needs either the `std` or the `libm` feature for its float math"
+);
+
+mod builder;
+mod constrain;
+mod contour;
+mod dedup;
+mod diagram;
+mod flip;
+pub mod generate;
+mod hull;
+mod insert;
+mod lloyd;
+mod locate;
+mod math;
+mod mesh;
+mod navmesh;
+mod neighbors;
+mod observe;
+mod order;
+mod predicates;
+mod progress;
+mod proximity;
+mod quality;
+mod regular;
+mod smooth;
+mod svg;
+mod tin;
+mod triangle_format;
 mod types;
 mod util;
+mod validate;
+
+pub use builder::{BuildError, InsertionOrder, TriangulationBuilder};
+pub use constrain::ConstraintError;
+pub use contour::{Isoband, Isoline};
+pub use dedup::{Dedup, Tolerance};
+pub use diagram::{Cell, Diagram};
+pub use flip::FlipError;
+pub use hull::{convex_hull, Circle, Rectangle};
+pub use lloyd::{Lloyd, Relaxation};
+pub use locate::Location;
+pub use mesh::Mesh;
+pub use navmesh::NavMesh;
+pub use observe::{Event, Observer, Recorder};
+#[cfg(target_has_atomic = "ptr")]
+pub use progress::CancelToken;
+pub use progress::{Cancelled, Progress};
+pub use quality::{Distribution, Histogram, QualityReport, TriangleQuality};
+pub use regular::{triangulate_weighted, RegularTriangulation};
+pub use smooth::{Smoother, Smoothing, SmoothingMethod};
+pub use svg::{Stroke, Svg, Viewport};
+pub use tin::{Simplifier, Tin};
+pub use triangle_format::{EleFile, FormatError, NeighFile, NodeFile, PolyFile, Region};
+pub use types::{EdgeIndex, VertIndex, Vertex, WeightedVertex};
+pub use validate::ValidationError;
+
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
 
 use types::*;
 use util::*;
 
+// how many edges legalize has room for before its stack grows
+const DEFAULT_FLIP_STACK: usize = 256;
+
+/// Triangulates `points` with the default options of [`TriangulationBuilder`].
 pub fn triangulate(points: Vec<impl Into<Vertex>>) -> (Triangulation, HullContext) {
-    let points = points.into_iter().map(Into::into).collect::<Vec<_>>();
-    let mut triangulation = Triangulation::new(points);
-    let mut hull = triangulation.context();
-    triangulation.update_with(&mut hull);
-    (triangulation, hull)
+    TriangulationBuilder::new(points)
+        .build()
+        .expect("building without constraints or validation cannot fail")
 }
 
 #[derive(Debug)]
@@ -18,27 +91,52 @@ pub struct Triangulation {
     triangles: Vec<VertIndex>,
     half_edges: Vec<Option<EdgeIndex>>,
     hull: Vec<VertIndex>,
+    dedup: Dedup,
+    duplicate_of: Vec<Option<VertIndex>>,
+    robust: bool,
+    flip_stack: usize,
+    constraints: BTreeSet<(VertIndex, VertIndex)>,
+    vertex_index: Option<Vec<Option<EdgeIndex>>>,
+    // flips without a hull context moved hull edges to other slots
+    hull_moved: bool,
 }
 
 /// Port of https://github.com/mapbox/delaunator/blob/main/index.js
 impl Triangulation {
     fn new(points: Vec<Vertex>) -> Self {
-        let n = points.len();
-        let max_triangles = (2 * n - 5).max(0);
-
-        Self {
+        let mut triangulation = Self {
             points,
-            triangles: vec![VertIndex::default(); max_triangles * 3],
-            half_edges: vec![None; max_triangles * 3],
-            hull: vec![VertIndex::default(); n],
-        }
+            triangles: Vec::new(),
+            half_edges: Vec::new(),
+            hull: Vec::new(),
+            dedup: Dedup::default(),
+            duplicate_of: Vec::new(),
+            robust: false,
+            flip_stack: DEFAULT_FLIP_STACK,
+            constraints: BTreeSet::new(),
+            vertex_index: None,
+            hull_moved: false,
+        };
+        triangulation.reserve();
+        triangulation
+    }
+
+    // size the buffers for the largest possible output, reusing their allocations after an update
+    fn reserve(&mut self) {
+        let n = self.points.len();
+        let max_triangles = (2 * n).saturating_sub(5);
+
+        self.triangles
+            .resize(max_triangles * 3, VertIndex::default());
+        self.half_edges.resize(max_triangles * 3, None);
+        self.hull.resize(n, VertIndex::default());
     }
 
     pub fn edges(&self) -> impl Iterator<Item = (Vertex, Vertex)> + '_ {
         self.half_edges
             .iter()
             .enumerate()
-            .filter(|(e, opposite)| opposite.map(|o| *e > *o).unwrap_or(true))
+            .filter(|(e, opposite)| opposite.map(|o| *e > o.get()).unwrap_or(true))
             .map(|(e, _)| {
                 (
                     self.points[self.triangles[e]],
@@ -52,15 +150,27 @@ impl Triangulation {
     }
 
     pub fn next_half_edge(e: EdgeIndex) -> EdgeIndex {
-        if *e % 3 == 2 {
-            (*e - 2).into()
+        if e.get() % 3 == 2 {
+            (e.get() - 2).into()
+        } else {
+            (e.get() + 1).into()
+        }
+    }
+
+    pub fn prev_half_edge(e: EdgeIndex) -> EdgeIndex {
+        if e.get().is_multiple_of(3) {
+            (e.get() + 2).into()
         } else {
-            (*e + 1).into()
+            (e.get() - 1).into()
         }
     }
 
     pub fn context(&self) -> HullContext {
-        HullContext::new(self.points.len())
+        HullContext::new(self.points.len(), self.flip_stack)
+    }
+
+    pub fn points(&self) -> &[Vertex] {
+        &self.points
     }
 
     pub fn triangles(&self) -> &[VertIndex] {
@@ -75,16 +185,117 @@ impl Triangulation {
         &self.hull
     }
 
+    // one incoming half-edge per point, preferring hull edges so walks around a point cover its whole star
+    pub(crate) fn inedges(&self) -> Vec<Option<EdgeIndex>> {
+        let mut inedges = vec![None; self.points.len()];
+        for e in 0..self.triangles.len() {
+            let p = self.triangles[Self::next_half_edge(e.into())];
+            if self.half_edges[e].is_none() || inedges[p].is_none() {
+                inedges[p] = Some(e.into());
+            }
+        }
+        inedges
+    }
+
+    /// For every point an edge leaving it, or `None` for points not in the triangulation,
+    /// if the triangulation was built to keep such an index.
+    pub fn vertex_edges(&self) -> Option<&[Option<EdgeIndex>]> {
+        self.vertex_index.as_deref()
+    }
+
+    // an edge leaving point v, from the vertex index when there is one
+    pub(crate) fn edge_from(&self, v: VertIndex) -> Option<EdgeIndex> {
+        match &self.vertex_index {
+            Some(index) => index[v],
+            None => self.triangles.iter().position(|&u| u == v).map(Into::into),
+        }
+    }
+
+    pub(crate) fn index_vertices(&mut self) {
+        let mut index = vec![None; self.points.len()];
+        for (e, &v) in self.triangles.iter().enumerate() {
+            index[v] = Some(e.into());
+        }
+        self.vertex_index = Some(index);
+    }
+
+    // the incoming half-edges around the point that `start` points to
+    pub(crate) fn edges_around_point(
+        &self,
+        start: EdgeIndex,
+    ) -> impl Iterator<Item = EdgeIndex> + '_ {
+        let mut incoming = Some(start);
+        core::iter::from_fn(move || {
+            let e = incoming?;
+            incoming = self.half_edges[Self::next_half_edge(e)].filter(|&i| i != start);
+            Some(e)
+        })
+    }
+
     pub fn update(&mut self) {
         let mut hull = self.context();
         self.update_with(&mut hull);
     }
 
     pub fn update_with(&mut self, hull: &mut HullContext) {
-        let mut ids: Vec<VertIndex> = (0..self.points.len()).map(|i| i.into()).collect();
-        let hash_size = (self.points.len() as f64).sqrt().ceil();
+        self.update_with_progress(hull, &mut Progress::new())
+            .expect("an update without a token cannot be cancelled");
+    }
 
-        let ((p0, p1, p2), seed) = match seed_triangle(&self.points) {
+    /// [`update_with`](Self::update_with), reporting to `progress` and stopping when its
+    /// token is cancelled. A cancelled update leaves no triangles and an empty hull
+    /// rather than part of the result; constraints are kept for the next update.
+    pub fn update_with_progress(
+        &mut self,
+        hull: &mut HullContext,
+        progress: &mut Progress,
+    ) -> Result<(), Cancelled> {
+        self.update_observed(hull, progress, &mut ())
+    }
+
+    /// [`update_with_progress`](Self::update_with_progress), telling `observer` about
+    /// every step of the sweep, see [`Event`].
+    pub fn update_observed(
+        &mut self,
+        hull: &mut HullContext,
+        progress: &mut Progress,
+        observer: &mut impl Observer,
+    ) -> Result<(), Cancelled> {
+        if hull.hash_size != math::ceil(math::sqrt(self.points.len() as f64)) {
+            // points were inserted since the context was made, and the hash has to grow
+            *hull = self.context();
+        }
+        self.reserve();
+        self.hull_moved = false;
+        self.find_duplicates(true);
+        for (i, of) in self.duplicate_of.iter().enumerate() {
+            if let Some(of) = *of {
+                observer.observe(Event::Duplicate {
+                    point: i.into(),
+                    of,
+                });
+            }
+        }
+        let constraints = core::mem::take(&mut self.constraints);
+        // the index is rebuilt at the end rather than patched along the way
+        let indexed = self.vertex_index.take().is_some();
+        let mut ids: Vec<VertIndex> = (0..self.points.len())
+            .filter(|&i| self.duplicate_of[i].is_none())
+            .map(|i| i.into())
+            .collect();
+        let hash_size = math::ceil(math::sqrt(self.points.len() as f64));
+
+        // only points that are not duplicates may seed the triangulation
+        let seed = if ids.len() == self.points.len() {
+            seed_triangle(&self.points)
+        } else {
+            let kept = ids.iter().map(|&i| self.points[i]).collect::<Vec<_>>();
+            seed_triangle(&kept).map(|(corners, seed)| {
+                let (a, b, c) = seed.abc();
+                (corners, VertTriple::new(ids[a], ids[b], ids[c]))
+            })
+        };
+        let ((p0, p1, p2), seed) = match seed {
             Ok(v) => v,
             Err(_) => {
                 // Degenerate case where all points are in a line
@@ -97,6 +308,9 @@ impl Triangulation {
 
                 ids.sort_by(|&a, &b| dists[a].total_cmp(&dists[b]));
 
+                self.triangles.clear();
+                self.half_edges.clear();
+                self.hull.clear();
                 let mut d0 = f64::NEG_INFINITY;
                 for id in ids {
                     let d = dists[id];
@@ -105,7 +319,12 @@ impl Triangulation {
                         d0 = d;
                     }
                 }
-                return;
+                self.constraints = constraints;
+                if indexed {
+                    self.index_vertices();
+                }
+                progress.finish(self.points.len());
+                return Ok(());
             }
         };
         let (i0, i1, i2) = seed.abc();
@@ -117,27 +336,53 @@ impl Triangulation {
             .iter()
             .map(|p| p.distance_squared(center))
             .collect::<Vec<_>>();
-        // sort the points by distance from the seed triangle circumcenter
-        ids.sort_by(|&a, &b| dists[a].total_cmp(&dists[b]));
+        // sort the points by distance from the seed triangle circumcenter, with ties broken
+        // by position so that equal points, the seed points included, end up next to each
+        // other, first to last
+        let points = &self.points;
+        ids.sort_by(|&a, &b| {
+            let (p, q) = (points[a], points[b]);
+            dists[a]
+                .total_cmp(&dists[b])
+                .then(p.x().total_cmp(&q.x()))
+                .then(p.y().total_cmp(&q.y()))
+        });
 
         hull.seed((p0, p1, p2), (i0, i1, i2), center);
+        observer.observe(Event::Seed([i0, i1, i2]));
 
         let mut triangles_len = 0;
-        self.add_triangle(&mut triangles_len, seed, TriTriple::NONE);
-        let mut p_prev = None;
-        'a: for i in ids {
+        self.add_triangle(observer, &mut triangles_len, seed, TriTriple::NONE);
+        let total = self.points.len();
+        let sweep_dedup = self.dedup.in_sweep();
+        let mut prev: Option<VertIndex> = None;
+        'a: for (k, i) in ids.into_iter().enumerate() {
+            if progress.check(k, total).is_err() {
+                self.constraints = constraints;
+                if indexed {
+                    self.vertex_index = Some(Vec::new());
+                }
+                return Err(self.abandon());
+            }
             let p = self.points[i];
 
-            // skip near-duplicate points
-            if p_prev.map_or(false, |pp| p.distance_squared(pp) <= f64::EPSILON * 2.0) {
+            // skip seed triangle points
+            if i == i0 || i == i1 || i == i2 {
+                prev = Some(i);
                 continue;
             }
-            p_prev = Some(p);
 
-            // skip seed triangle points
-            if i == i0 || i == i1 || i == i2 {
+            // with the default tolerance, near-duplicates follow the point they duplicate
+            if let Some(j) = prev.filter(|&j| {
+                sweep_dedup && p.distance_squared(self.points[j]) <= 2.0 * f64::EPSILON
+            }) {
+                // the previous point may itself have been dropped near the hull
+                let j = self.duplicate_of[j].unwrap_or(j);
+                self.duplicate_of[i] = Some(j);
+                observer.observe(Event::Duplicate { point: i, of: j });
                 continue;
             }
+            prev = Some(i);
 
             // find a visible edge on the convex hull using edge hash
             let key = hash_key(p, center, hash_size);
@@ -152,10 +397,12 @@ impl Triangulation {
             let sstart = hull.prev[start.unwrap()];
             let mut e = sstart;
             let mut q = hull.next[e];
-            while orient2d_fast(p, self.points[e], self.points[q]) >= 0.0 {
+            while self.orient(p, self.points[e], self.points[q]) >= 0.0 {
                 e = q;
                 if e == sstart {
                     // likely a near-duplicate point; skip it
+                    self.duplicate_of[i] = Some(e);
+                    observer.observe(Event::Duplicate { point: i, of: e });
                     continue 'a;
                 }
                 q = hull.next[e];
@@ -163,26 +410,28 @@ impl Triangulation {
 
             // add the first triangle from the point
             let mut t = self.add_triangle(
+                observer,
                 &mut triangles_len,
                 VertTriple::new(e, i, hull.next[e]),
                 TriTriple::new(None, None, Some(hull.tri[e])),
             );
 
             // recursively flip triangles from the point until they satisfy the Delaunay condition
-            hull.tri[i] = self.legalize(hull, t + 2);
+            hull.tri[i] = self.legalize(hull, t + 2, observer);
             hull.tri[e] = t.into(); // keep track of boundary triangles on the hull
             hull.size += 1;
 
             // walk forward through the hull, adding more triangles and flipping recursively
             let mut n = hull.next[e];
             q = hull.next[n];
-            while orient2d_fast(p, self.points[n], self.points[q]) < 0.0 {
+            while self.orient(p, self.points[n], self.points[q]) < 0.0 {
                 t = self.add_triangle(
+                    observer,
                     &mut triangles_len,
                     VertTriple::new(n, i, q),
                     TriTriple::new(Some(hull.tri[i]), None, Some(hull.tri[n])),
                 );
-                hull.tri[i] = self.legalize(hull, t + 2);
+                hull.tri[i] = self.legalize(hull, t + 2, observer);
                 hull.next[n] = n;
                 hull.size -= 1;
                 n = q;
@@ -192,18 +441,19 @@ impl Triangulation {
             // walk backward from the other side, adding more triangles and flipping
             if e == sstart {
                 q = hull.prev[e];
-                while orient2d_fast(p, self.points[q], self.points[e]) < 0.0 {
+                while self.orient(p, self.points[q], self.points[e]) < 0.0 {
                     t = self.add_triangle(
+                        observer,
                         &mut triangles_len,
                         VertTriple::new(q, i, e),
                         TriTriple::new(None, Some(hull.tri[e]), Some(hull.tri[q])),
                     );
-                    self.legalize(hull, t + 2);
+                    self.legalize(hull, t + 2, observer);
                     hull.tri[q] = t.into();
                     hull.next[e] = e;
                     hull.size -= 1;
                     e = q;
-                    q = hull.next[e];
+                    q = hull.prev[e];
                 }
             }
 
@@ -217,6 +467,11 @@ impl Triangulation {
             // save the two new edges in the hash table
             hull.hash[hash_key(p, center, hash_size)] = Some(i);
             hull.hash[hash_key(self.points[e], center, hash_size)] = Some(e);
+            observer.observe(Event::Inserted(i));
+            observer.observe(Event::Hull {
+                start: e,
+                size: hull.size,
+            });
         }
 
         let mut e = hull.start;
@@ -228,10 +483,18 @@ impl Triangulation {
         self.triangles.truncate(triangles_len);
         self.half_edges.truncate(triangles_len);
         self.hull.truncate(hull.size);
+
+        if indexed {
+            self.index_vertices();
+        }
+        self.recover_constraints(hull, constraints);
+        progress.finish(total);
+        Ok(())
     }
 
     fn add_triangle(
         &mut self,
+        observer: &mut impl Observer,
         triangles_len: &mut usize,
         vert_ids: VertTriple,
         half_ids: TriTriple,
@@ -247,6 +510,10 @@ impl Triangulation {
         self.link(t + 2, half_ids.c());
 
         *triangles_len += 3;
+        observer.observe(Event::Triangle {
+            first: t.into(),
+            corners: [vert_ids.a(), vert_ids.b(), vert_ids.c()],
+        });
         t
     }
 
@@ -257,64 +524,53 @@ impl Triangulation {
         }
     }
 
-    fn legalize(&mut self, hull: &mut HullContext, mut a: usize) -> EdgeIndex {
-        let mut i = 0;
+    fn legalize(
+        &mut self,
+        hull: &mut HullContext,
+        mut a: usize,
+        observer: &mut impl Observer,
+    ) -> EdgeIndex {
         let mut ar;
 
-        // recursion eliminated with a fixed-size stack
+        // recursion eliminated with a stack that grows as needed
+        hull.edge_stack.clear();
         loop {
             let b = self.half_edges[a];
 
-            /* if the pair of triangles doesn't satisfy the Delaunay condition
-             * (p1 is inside the circumcircle of [p0, pl, pr]), flip them,
-             * then do the same check/flip recursively for the new pair of triangles
-             *
-             *           pl                    pl
-             *          /||\                  /  \
-             *       al/ || \bl            al/    \a
-             *        /  ||  \              /      \
-             *       /  a||b  \    flip    /___ar___\
-             *     p0\   ||   /p1   =>   p0\---bl---/p1
-             *        \  ||  /              \      /
-             *       ar\ || /br             b\    /br
-             *          \||/                  \  /
-             *           pr                    pr
-             */
+            // if the pair of triangles doesn't satisfy the Delaunay condition
+            // (p1 is inside the circumcircle of [p0, pl, pr]), flip them,
+            // then do the same check/flip recursively for the new pair of triangles
             let a0 = a - a % 3;
             ar = a0 + (a + 2) % 3;
 
             let Some(b) = b else {
                 // convex hull edge
-                if i == 0 {
-                    break;
+                match hull.edge_stack.pop() {
+                    Some(next) => a = next,
+                    None => break,
                 }
-                i -= 1;
-                a = hull.edge_stack[i];
                 continue;
             };
 
-            let b0 = *b - *b % 3;
+            let b0 = b.get() - b.get() % 3;
             let al = a0 + (a + 1) % 3;
-            let bl = (b0 + (*b + 2) % 3).into();
+            let bl: EdgeIndex = (b0 + (b.get() + 2) % 3).into();
 
             let p0: VertIndex = self.triangles[ar];
             let pr: VertIndex = self.triangles[a];
             let pl: VertIndex = self.triangles[al];
             let p1: VertIndex = self.triangles[bl];
 
-            if in_circle(
-                self.points[p0],
-                self.points[pr],
-                self.points[pl],
-                self.points[p1],
-            ) {
-                self.triangles[a] = p1;
-                self.triangles[b] = p0;
-
-                let hbl: Option<EdgeIndex> = self.half_edges[bl];
-
+            if !self.is_constrained(a.into())
+                && self.in_circumcircle(
+                    self.points[p0],
+                    self.points[pr],
+                    self.points[pl],
+                    self.points[p1],
+                )
+            {
                 // edge swapped on the other side of the hull (rare); fix the halfedge reference
-                if hbl.is_none() {
+                if self.half_edges[bl].is_none() {
                     let mut e = hull.start;
                     loop {
                         if hull.tri[e] == bl {
@@ -327,23 +583,19 @@ impl Triangulation {
                         }
                     }
                 }
-                self.link(a, hbl);
-                self.link(*b, self.half_edges[ar]);
-                self.link(ar, Some(bl));
-
-                let br = b0 + (*b + 1) % 3;
-
-                // don't worry about hitting the cap: it can only happen on extremely degenerate input
-                if i < hull.edge_stack.len() {
-                    hull.edge_stack[i] = br;
-                    i += 1;
-                }
+                self.swap_diagonal(a, b);
+                observer.observe(Event::Flip {
+                    edge: a.into(),
+                    twin: b,
+                });
+
+                let br = b0 + (b.get() + 1) % 3;
+                hull.edge_stack.push(br);
             } else {
-                if i == 0 {
-                    break;
+                match hull.edge_stack.pop() {
+                    Some(next) => a = next,
+                    None => break,
                 }
-                i -= 1;
-                a = hull.edge_stack[i];
             }
         }
         ar.into()
@@ -356,22 +608,22 @@ pub struct HullContext {
     next: Vec<VertIndex>,
     tri: Vec<EdgeIndex>,
     hash: Vec<Option<VertIndex>>,
-    edge_stack: Box<[usize; 256]>,
+    edge_stack: Vec<usize>,
     hash_size: f64,
     start: VertIndex,
     size: usize,
 }
 
 impl HullContext {
-    fn new(n: usize) -> Self {
-        let hash_size = (n as f64).sqrt().ceil();
+    fn new(n: usize, flip_stack: usize) -> Self {
+        let hash_size = math::ceil(math::sqrt(n as f64));
 
         Self {
             prev: vec![VertIndex::default(); n],
             next: vec![VertIndex::default(); n],
             tri: vec![EdgeIndex::default(); n],
             hash: vec![None; hash_size as usize],
-            edge_stack: Box::new([0; 256]),
+            edge_stack: Vec::with_capacity(flip_stack),
             hash_size,
             start: VertIndex::default(),
             size: 0,
@@ -443,6 +695,12 @@ mod tests {
         .is_nan());
     }
 
+    #[test]
+    #[should_panic(expected = "needs more than 32 bits")]
+    fn test_index_range() {
+        let _ = EdgeIndex::from(u32::MAX as usize);
+    }
+
     #[test]
     fn test_circumcenter() {
         // Normal case
@@ -450,7 +708,7 @@ mod tests {
             circumcenter(
                 Vertex::new(0.0, 0.0),
                 Vertex::new(1.0, 0.0),
-                Vertex::new(std::f64::consts::FRAC_PI_4, std::f64::consts::FRAC_PI_4),
+                Vertex::new(core::f64::consts::FRAC_PI_4, core::f64::consts::FRAC_PI_4),
             ),
             Vertex::new(0.5, 0.2853982)
         );
@@ -476,6 +734,178 @@ mod tests {
         )
     }
 
+    // checks the structure of a triangulation of distinct points
+    fn validate(t: &Triangulation) {
+        let points = t.points();
+        let n = t.triangles().len();
+        for e in 0..n {
+            let e = EdgeIndex::from(e);
+            if let Some(twin) = t.half_edges()[e] {
+                assert_eq!(t.half_edges()[twin], Some(e));
+                assert_eq!(
+                    t.triangles()[e],
+                    t.triangles()[Triangulation::next_half_edge(twin)]
+                );
+            }
+        }
+        for tri in t.triangles().chunks_exact(3) {
+            let (a, b, c) = (points[tri[0]], points[tri[1]], points[tri[2]]);
+            assert!(orient2d_fast(a, b, c) > 0.0);
+        }
+
+        let hull = t.hull();
+        for i in 0..hull.len() {
+            let (a, b, c) = (
+                points[hull[i]],
+                points[hull[(i + 1) % hull.len()]],
+                points[hull[(i + 2) % hull.len()]],
+            );
+            assert!(orient2d_fast(a, b, c) >= 0.0, "hull is not convex");
+        }
+        let boundary = t.half_edges().iter().filter(|h| h.is_none()).count();
+        assert_eq!(boundary, hull.len());
+        assert_eq!(n / 3, 2 * points.len() - 2 - hull.len());
+
+        // locally Delaunay, up to rounding on cocircular input
+        for e in 0..n {
+            let e = EdgeIndex::from(e);
+            let Some(twin) = t.half_edges()[e] else {
+                continue;
+            };
+            let tri = e.get() / 3 * 3;
+            let (a, b, c) = (
+                points[t.triangles()[tri]],
+                points[t.triangles()[tri + 1]],
+                points[t.triangles()[tri + 2]],
+            );
+            let p = points[t.triangles()[Triangulation::prev_half_edge(twin)]];
+            let r = circumradius(a, b, c).sqrt();
+            let d = p.distance_squared(circumcenter(a, b, c)).sqrt();
+            assert!(d >= r * (1.0 - 1e-9), "edge {e:?} is not Delaunay");
+        }
+    }
+
+    #[test]
+    fn test_backward_hull_walk() {
+        // the last point sees two hull edges behind the start of the hull, so the walk
+        // back from it has to keep following `prev`
+        let (t, _) = triangulate(vec![
+            (12.0, 31.0),
+            (22.0, 12.0),
+            (23.0, 24.0),
+            (23.0, 39.0),
+            (31.0, 38.0),
+            (36.0, 10.0),
+            (42.0, 36.0),
+            (52.0, 18.0),
+            (53.0, 12.0),
+            (54.0, 36.0),
+        ]);
+        validate(&t);
+        assert_eq!(t.hull(), [3, 9, 8, 5, 1, 0].map(VertIndex::from));
+    }
+
+    #[test]
+    fn test_repeated_points() {
+        // the last point repeats a corner of the seed triangle, all four on one circle
+        let (t, _) = triangulate(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (0.0, 0.0)]);
+        assert_eq!(t.duplicate_of(), [None, None, None, Some(0.into())]);
+        assert_eq!(t.triangles().len(), 3);
+
+        for seed in 0..100 {
+            let mut points = generate::uniform(50, Vertex::ZERO, Vertex::splat(1.0), seed);
+            let k = seed as usize % 50;
+            points.push(points[k]);
+            let (t, _) = triangulate(points);
+            assert_eq!(t.validate(), Ok(()));
+            assert_eq!(t.duplicate_of()[50], Some(k.into()));
+        }
+    }
+
+    #[test]
+    fn test_generated_point_sets() {
+        let (min, max) = (Vertex::ZERO, Vertex::new(3.0, 2.0));
+        for seed in 0..4 {
+            for points in [
+                generate::uniform(1000, min, max, seed),
+                generate::poisson_disk(min, max, 0.08, seed),
+                generate::jittered_grid(min, max, 30, 20, 0.9, seed),
+                generate::halton(1000, min, max, seed),
+                generate::sobol(1000, min, max, seed),
+                generate::gaussian_clusters(
+                    1000,
+                    &[Vertex::new(0.5, 0.5), Vertex::new(2.5, 1.0)],
+                    0.2,
+                    seed,
+                ),
+            ] {
+                let (t, _) = triangulate(points);
+                validate(&t);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cocircular_point_sets() {
+        for seed in 0..4 {
+            let (t, _) = triangulate(generate::circles(Vertex::ZERO, &[1.0, 2.0, 3.0], 32, seed));
+            validate(&t);
+        }
+        let (t, _) = triangulate(generate::jittered_grid(
+            Vertex::ZERO,
+            Vertex::splat(1.0),
+            20,
+            20,
+            0.0,
+            0,
+        ));
+        validate(&t);
+    }
+
+    #[test]
+    fn test_index_size() {
+        // a twin costs no more than an index, and both half of a usize
+        assert_eq!(core::mem::size_of::<VertIndex>(), 4);
+        assert_eq!(core::mem::size_of::<Option<EdgeIndex>>(), 4);
+        assert_eq!(EdgeIndex::from(7).get(), 7);
+        assert!(EdgeIndex::from(0) < EdgeIndex::from(1));
+    }
+
+    #[test]
+    fn test_cocircular_stress() {
+        // every square of a grid is cocircular, and so is every ring of a polar grid
+        let grid = (0..40 * 40)
+            .map(|k| Vertex::new((k % 40) as f64, (k / 40) as f64))
+            .collect::<Vec<_>>();
+        let rings = (1..=12)
+            .flat_map(|r| {
+                (0..64).map(move |k| {
+                    let angle = k as f64 * core::f64::consts::TAU / 64.0;
+                    Vertex::new(r as f64 * angle.cos(), r as f64 * angle.sin())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for points in [grid, rings] {
+            let (t, hull) = TriangulationBuilder::new(points.clone())
+                .robust(true)
+                .flip_stack(1)
+                .validate(true)
+                .build()
+                .unwrap();
+            validate(&t);
+            assert!(hull.edge_stack.capacity() > 1);
+
+            // no point lies inside the circumcircle of any triangle
+            for tri in t.triangles().chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|k| points[tri[k]]);
+                assert!(points
+                    .iter()
+                    .all(|&p| !predicates::in_circle_exact(a, b, c, p)));
+            }
+        }
+    }
+
     #[test]
     fn test_delaunator() {
         let points = POINTS.into();
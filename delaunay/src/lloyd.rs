@@ -0,0 +1,210 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::math;
+use crate::types::{VertIndex, Vertex};
+use crate::util::{polygon_area, polygon_centroid};
+use crate::{HullContext, Triangulation};
+
+/// Lloyd relaxation towards a centroidal Voronoi tessellation of a convex region.
+///
+/// Every iteration clips the Voronoi cells to the region, moves each point to the
+/// centroid of its cell, optionally weighted by a density, and re-triangulates in place.
+pub struct Lloyd<'a> {
+    region: Vec<Vertex>,
+    density: Option<Box<dyn Fn(Vertex) -> f64 + 'a>>,
+    tolerance: f64,
+    max_iterations: usize,
+}
+
+/// The outcome of [`Lloyd::relax`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Relaxation {
+    /// The number of iterations performed.
+    pub iterations: usize,
+    /// The largest distance a point moved in the last iteration.
+    pub displacement: f64,
+    /// Whether `displacement` fell below the tolerance before the iteration cap.
+    pub converged: bool,
+}
+
+impl<'a> Lloyd<'a> {
+    /// Relaxation inside a convex polygon of either winding.
+    pub fn new(region: Vec<Vertex>) -> Self {
+        Self {
+            region,
+            density: None,
+            tolerance: 1e-6,
+            max_iterations: 100,
+        }
+    }
+
+    /// Relaxation inside the axis-aligned rectangle spanned by `min` and `max`.
+    pub fn rect(min: Vertex, max: Vertex) -> Self {
+        Self::new(vec![
+            min,
+            Vertex::new(max.x(), min.y()),
+            max,
+            Vertex::new(min.x(), max.y()),
+        ])
+    }
+
+    /// Weights the centroids by a non-negative density, concentrating points where it is high.
+    pub fn density(mut self, density: impl Fn(Vertex) -> f64 + 'a) -> Self {
+        self.density = Some(Box::new(density));
+        self
+    }
+
+    /// Stop once no point moves further than this in an iteration.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Relaxes the points of an up-to-date triangulation, reusing its buffers and `hull`.
+    /// Points without a cell inside the region stay where they are.
+    pub fn relax(&self, triangulation: &mut Triangulation, hull: &mut HullContext) -> Relaxation {
+        let mut relaxation = Relaxation {
+            iterations: 0,
+            displacement: 0.0,
+            converged: false,
+        };
+
+        while relaxation.iterations < self.max_iterations {
+            let voronoi = triangulation.voronoi();
+            let mut displacement = 0.0_f64;
+            for i in 0..triangulation.points.len() {
+                let cell = voronoi.clip(VertIndex::from(i), &self.region);
+                if let Some(centroid) = self.centroid(&cell) {
+                    let p = &mut triangulation.points[i];
+                    displacement = displacement.max(p.distance_squared(centroid));
+                    *p = centroid;
+                }
+            }
+
+            triangulation.update_with(hull);
+            relaxation.iterations += 1;
+            relaxation.displacement = math::sqrt(displacement);
+            if relaxation.displacement <= self.tolerance {
+                relaxation.converged = true;
+                break;
+            }
+        }
+        relaxation
+    }
+
+    fn centroid(&self, cell: &[Vertex]) -> Option<Vertex> {
+        if cell.len() < 3 || polygon_area(cell) == 0.0 {
+            return None;
+        }
+        let Some(density) = &self.density else {
+            return Some(polygon_centroid(cell));
+        };
+
+        // midpoint quadrature over a fan of the cell, each triangle split into 16
+        let mut mass = 0.0;
+        let mut moment = Vertex::ZERO;
+        for k in 1..cell.len() - 1 {
+            let (a, b, c) = (cell[0], cell[k], cell[k + 1]);
+            let area = polygon_area(&[a, b, c]).abs() / 16.0;
+            let (u, v) = ((b - a) / 4.0, (c - a) / 4.0);
+            for i in 0..4 {
+                for j in 0..4 - i {
+                    let corner = a + u * i as f64 + v * j as f64;
+                    let mut centers = vec![corner + (u + v) / 3.0];
+                    if i + j < 3 {
+                        centers.push(corner + (u + v) * (2.0 / 3.0));
+                    }
+                    for center in centers {
+                        let w = density(center) * area;
+                        mass += w;
+                        moment = moment + center * w;
+                    }
+                }
+            }
+        }
+
+        (mass > 0.0).then(|| moment / mass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate};
+
+    fn min_distance(points: &[Vertex]) -> f64 {
+        let mut d = f64::INFINITY;
+        for (i, p) in points.iter().enumerate() {
+            for q in &points[i + 1..] {
+                d = d.min(p.distance_squared(*q));
+            }
+        }
+        d.sqrt()
+    }
+
+    #[test]
+    fn test_relax_spreads_points() {
+        let points = generate::uniform(100, Vertex::ZERO, Vertex::splat(1.0), 2);
+        let before = min_distance(&points);
+        let (mut t, mut hull) = triangulate(points);
+
+        let r = Lloyd::rect(Vertex::ZERO, Vertex::splat(1.0))
+            .tolerance(1e-3)
+            .max_iterations(500)
+            .relax(&mut t, &mut hull);
+
+        assert!(r.converged);
+        assert!(r.displacement <= 1e-3);
+        assert!(min_distance(t.points()) > 2.0 * before);
+        assert!(t
+            .points()
+            .iter()
+            .all(|p| (0.0..=1.0).contains(&p.x()) && (0.0..=1.0).contains(&p.y())));
+        // the triangulation was rebuilt for the relaxed points
+        assert_eq!(t.triangles().len() / 3, 2 * 100 - 2 - t.hull().len());
+    }
+
+    #[test]
+    fn test_density() {
+        let left = |density: f64| {
+            let (mut t, mut hull) =
+                triangulate(generate::uniform(100, Vertex::ZERO, Vertex::splat(1.0), 3));
+            let r = Lloyd::rect(Vertex::ZERO, Vertex::splat(1.0))
+                .density(|p| if p.x() < 0.5 { density } else { 1.0 })
+                .max_iterations(30)
+                .relax(&mut t, &mut hull);
+            assert_eq!(r.iterations, 30);
+            t.points().iter().filter(|p| p.x() < 0.5).count()
+        };
+
+        let (uniform, dense) = (left(1.0), left(9.0));
+        assert!(
+            dense >= uniform + 8,
+            "{uniform} vs {dense} points on the dense side"
+        );
+    }
+
+    #[test]
+    fn test_clipped_cells_tile_region() {
+        let (t, _) = triangulate(generate::uniform(50, Vertex::ZERO, Vertex::splat(1.0), 4));
+        let region = [
+            Vertex::new(-0.5, -0.5),
+            Vertex::new(1.5, -0.5),
+            Vertex::new(1.5, 1.5),
+            Vertex::new(-0.5, 1.5),
+        ];
+        let voronoi = t.voronoi();
+
+        let area = (0..50)
+            .map(|i| polygon_area(&voronoi.clip(i.into(), &region)).abs())
+            .sum::<f64>();
+        assert!((area - 4.0).abs() < 1e-9, "{area}");
+    }
+}
@@ -0,0 +1,154 @@
+use crate::generate::Rng;
+use crate::predicates::orient2d;
+use crate::types::{EdgeIndex, VertIndex, Vertex};
+use crate::Triangulation;
+
+/// Where a point lies relative to a triangulation, see [`Triangulation::locate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// Strictly inside the triangle with this index.
+    Triangle(usize),
+    /// On the interior of this half-edge.
+    Edge(EdgeIndex),
+    /// On this vertex.
+    Vertex(VertIndex),
+    /// Beyond this hull edge.
+    Outside(EdgeIndex),
+}
+
+impl Triangulation {
+    /// Finds the triangle, edge or vertex containing `p` by walking from the first triangle.
+    pub fn locate(&self, p: Vertex) -> Location {
+        self.locate_from(p, 0)
+    }
+
+    /// Finds the triangle, edge or vertex containing `p` by walking from triangle `hint`.
+    /// The walk is fastest when `hint` is close to `p`, as for a sequence of nearby queries.
+    /// Empty triangulations locate everything outside of edge 0.
+    pub fn locate_from(&self, p: Vertex, hint: usize) -> Location {
+        self.locate_edge(p, hint).0
+    }
+
+    // also returns an edge of the triangle the walk ended in, which has the point
+    // as a corner when it is found on a vertex
+    pub(crate) fn locate_edge(&self, p: Vertex, hint: usize) -> (Location, EdgeIndex) {
+        walk(
+            &self.triangles,
+            &self.half_edges,
+            |v| self.points[v],
+            p,
+            hint,
+        )
+    }
+}
+
+// A visibility walk from triangle `hint` over any half-edge structure, `point` giving
+// the position of a vertex. Starting each triangle's test at a random edge and testing
+// orientation exactly, the walk cannot cycle even where the triangulation is not
+// Delaunay (Devillers, Pion and Teillaud).
+pub(crate) fn walk(
+    triangles: &[VertIndex],
+    half_edges: &[Option<EdgeIndex>],
+    point: impl Fn(VertIndex) -> Vertex,
+    p: Vertex,
+    hint: usize,
+) -> (Location, EdgeIndex) {
+    let count = triangles.len() / 3;
+    if count == 0 {
+        return (Location::Outside(0.into()), 0.into());
+    }
+
+    let mut rng = Rng::new(hint as u64);
+    let mut t = hint.min(count - 1) * 3;
+    'walk: loop {
+        let (mut zeros, mut count) = ([EdgeIndex::default(); 2], 0);
+        let start = (rng.next_u64() % 3) as usize;
+        for k in 0..3 {
+            let e = EdgeIndex::from(t + (start + k) % 3);
+            let o = orient2d(
+                point(triangles[e.get()]),
+                point(triangles[Triangulation::next_half_edge(e).get()]),
+                p,
+            );
+            if o < 0.0 {
+                match half_edges[e.get()] {
+                    Some(f) => {
+                        t = f.get() - f.get() % 3;
+                        continue 'walk;
+                    }
+                    None => return (Location::Outside(e), e),
+                }
+            } else if o == 0.0 && count < 2 {
+                zeros[count] = e;
+                count += 1;
+            }
+        }
+        return match zeros[..count] {
+            [] => (Location::Triangle(t / 3), t.into()),
+            [e] => (Location::Edge(e), e),
+            [e, f] if Triangulation::next_half_edge(e) == f => {
+                (Location::Vertex(triangles[f.get()]), f)
+            }
+            [e, ..] => (Location::Vertex(triangles[e.get()]), e),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{generate, triangulate};
+
+    #[test]
+    fn test_locate() {
+        let (t, _) = triangulate(vec![
+            Vertex::new(0.0, 0.0),
+            Vertex::new(2.0, 0.0),
+            Vertex::new(2.0, 2.0),
+            Vertex::new(0.0, 2.0),
+            Vertex::new(1.0, 0.5),
+        ]);
+
+        assert_eq!(t.locate(Vertex::new(2.0, 2.0)), Location::Vertex(2.into()));
+        assert_eq!(t.locate(Vertex::new(1.0, 0.5)), Location::Vertex(4.into()));
+
+        let Location::Triangle(tri) = t.locate(Vertex::new(1.0, 1.5)) else {
+            panic!("expected a triangle");
+        };
+        assert!(t.triangles()[3 * tri..3 * tri + 3].contains(&3.into()));
+
+        let Location::Edge(e) = t.locate(Vertex::new(1.0, 0.0)) else {
+            panic!("expected an edge");
+        };
+        assert!(t.half_edges()[e].is_none());
+
+        let Location::Outside(e) = t.locate(Vertex::new(3.0, 1.0)) else {
+            panic!("expected outside");
+        };
+        let (a, b) = (
+            t.triangles()[e].get(),
+            t.triangles()[Triangulation::next_half_edge(e)].get(),
+        );
+        assert_eq!((a.min(b), a.max(b)), (1, 2));
+    }
+
+    #[test]
+    fn test_non_delaunay() {
+        let points = generate::uniform(300, Vertex::ZERO, Vertex::splat(1.0), 8);
+        let (mut t, _) = triangulate(points.clone());
+        // flip every other edge that allows it, leaving plenty of long thin triangles
+        for e in (0..t.triangles().len()).step_by(2) {
+            let _ = t.flip(e.into());
+        }
+        assert!((0..t.triangles().len()).any(|e| !t.is_locally_delaunay(e.into())));
+
+        let triangles = t.triangles().len() / 3;
+        for (i, &p) in points.iter().enumerate() {
+            for hint in [0, i % triangles, triangles - 1] {
+                assert_eq!(t.locate_from(p, hint), Location::Vertex(i.into()));
+            }
+        }
+    }
+}
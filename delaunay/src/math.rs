@@ -0,0 +1,90 @@
+//! The float functions that core lacks, from std when it is there and from libm otherwise.
+
+#[cfg(feature = "std")]
+mod imp {
+    pub fn sqrt(x: f64) -> f64 {
+        x.sqrt()
+    }
+
+    pub fn floor(x: f64) -> f64 {
+        x.floor()
+    }
+
+    pub fn ceil(x: f64) -> f64 {
+        x.ceil()
+    }
+
+    pub fn trunc(x: f64) -> f64 {
+        x.trunc()
+    }
+
+    pub fn sin(x: f64) -> f64 {
+        x.sin()
+    }
+
+    pub fn cos(x: f64) -> f64 {
+        x.cos()
+    }
+
+    pub fn acos(x: f64) -> f64 {
+        x.acos()
+    }
+
+    pub fn atan2(y: f64, x: f64) -> f64 {
+        y.atan2(x)
+    }
+
+    pub fn ln(x: f64) -> f64 {
+        x.ln()
+    }
+
+    pub fn mul_add(a: f64, b: f64, c: f64) -> f64 {
+        a.mul_add(b, c)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod imp {
+    pub use libm::{acos, atan2, ceil, cos, floor, sin, sqrt, trunc};
+
+    pub fn ln(x: f64) -> f64 {
+        libm::log(x)
+    }
+
+    pub fn mul_add(a: f64, b: f64, c: f64) -> f64 {
+        libm::fma(a, b, c)
+    }
+}
+
+pub(crate) use imp::*;
+
+pub(crate) fn fract(x: f64) -> f64 {
+    x - trunc(x)
+}
+
+pub(crate) fn rem_euclid(x: f64, m: f64) -> f64 {
+    let r = x % m;
+    if r < 0.0 {
+        r + m.abs()
+    } else {
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_math() {
+        assert_eq!(sqrt(2.25), 1.5);
+        assert_eq!((floor(-1.5), ceil(-1.5), trunc(-1.5)), (-2.0, -1.0, -1.0));
+        assert_eq!((fract(2.75), fract(-2.75)), (0.75, -0.75));
+        assert_eq!((rem_euclid(-1.0, 4.0), rem_euclid(5.0, -4.0)), (3.0, 1.0));
+        assert!((atan2(1.0, -1.0) - 3.0 * core::f64::consts::FRAC_PI_4).abs() < 1e-15);
+        assert!((ln(core::f64::consts::E) - 1.0).abs() < 1e-15);
+        // fused, so the rounding error of the product survives
+        let x = 1.0 + f64::EPSILON;
+        assert_eq!(mul_add(x, x, -(x * x)), f64::EPSILON * f64::EPSILON);
+    }
+}
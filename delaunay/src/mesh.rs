@@ -0,0 +1,333 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::math;
+use crate::Triangulation;
+
+/// A triangulation as a 3D surface, for writing to mesh files.
+///
+/// Triangles face up: their corners run counterclockwise seen from above, and normals
+/// point towards positive z.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mesh {
+    /// The points with their elevations.
+    pub positions: Vec<[f64; 3]>,
+    /// The unit normal at every point, averaged over its triangles weighted by area.
+    pub normals: Vec<[f64; 3]>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Triangulation {
+    /// The triangulation as a mesh, lifted to `z[i]` at point `i` or flat at zero.
+    ///
+    /// # Panics
+    ///
+    /// If `z` does not have an elevation for every point.
+    pub fn mesh(&self, z: Option<&[f64]>) -> Mesh {
+        if let Some(z) = z {
+            assert_eq!(z.len(), self.points.len());
+        }
+        let positions = self
+            .points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| [p.x(), p.y(), z.map_or(0.0, |z| z[i])])
+            .collect::<Vec<_>>();
+        // stored clockwise, so two corners swap
+        let triangles = self
+            .triangles
+            .chunks_exact(3)
+            .map(|t| [t[0], t[2], t[1]].map(|v| v.get() as u32))
+            .collect::<Vec<_>>();
+
+        // unnormalized face normals are as long as twice the area
+        let mut sums = vec![[0.0; 3]; positions.len()];
+        for t in &triangles {
+            let normal = face_normal(t.map(|v| positions[v as usize]));
+            for &v in t {
+                for k in 0..3 {
+                    sums[v as usize][k] += normal[k];
+                }
+            }
+        }
+        let normals = sums
+            .into_iter()
+            .map(|n| match normalize(n) {
+                // points outside the triangulation
+                [0.0, 0.0, 0.0] => [0.0, 0.0, 1.0],
+                n => n,
+            })
+            .collect();
+
+        Mesh {
+            positions,
+            normals,
+            triangles,
+        }
+    }
+}
+
+impl Mesh {
+    /// Wavefront OBJ, with a normal per vertex.
+    pub fn to_obj(&self) -> String {
+        let mut obj = String::new();
+        for [x, y, z] in &self.positions {
+            let _ = writeln!(obj, "v {x} {y} {z}");
+        }
+        for [x, y, z] in &self.normals {
+            let _ = writeln!(obj, "vn {x} {y} {z}");
+        }
+        for t in &self.triangles {
+            let [a, b, c] = t.map(|v| v + 1);
+            let _ = writeln!(obj, "f {a}//{a} {b}//{b} {c}//{c}");
+        }
+        obj
+    }
+
+    /// ASCII PLY, with a normal per vertex.
+    pub fn to_ply(&self) -> String {
+        let mut ply = self.ply_header("ascii");
+        for (p, n) in self.positions.iter().zip(&self.normals) {
+            let _ = writeln!(ply, "{} {} {} {} {} {}", p[0], p[1], p[2], n[0], n[1], n[2]);
+        }
+        for [a, b, c] in &self.triangles {
+            let _ = writeln!(ply, "3 {a} {b} {c}");
+        }
+        ply
+    }
+
+    /// Little-endian binary PLY, with single precision positions and normals.
+    pub fn to_ply_binary(&self) -> Vec<u8> {
+        let mut ply = self.ply_header("binary_little_endian").into_bytes();
+        for (p, n) in self.positions.iter().zip(&self.normals) {
+            for x in p.iter().chain(n) {
+                ply.extend((*x as f32).to_le_bytes());
+            }
+        }
+        for t in &self.triangles {
+            ply.push(3);
+            for v in t {
+                ply.extend(v.to_le_bytes());
+            }
+        }
+        ply
+    }
+
+    fn ply_header(&self, format: &str) -> String {
+        format!(
+            "ply\nformat {format} 1.0\nelement vertex {}\n\
+             property float x\nproperty float y\nproperty float z\n\
+             property float nx\nproperty float ny\nproperty float nz\n\
+             element face {}\nproperty list uchar uint vertex_indices\nend_header\n",
+            self.positions.len(),
+            self.triangles.len()
+        )
+    }
+
+    /// Binary STL. It has no shared vertices, so each triangle carries its face normal.
+    pub fn to_stl(&self) -> Vec<u8> {
+        let mut stl = vec![0; 80];
+        stl[..8].copy_from_slice(b"delaunay");
+        stl.extend((self.triangles.len() as u32).to_le_bytes());
+        for t in &self.triangles {
+            let corners = t.map(|v| self.positions[v as usize]);
+            let normal = normalize(face_normal(corners));
+            for x in normal.iter().chain(corners.iter().flatten()) {
+                stl.extend((*x as f32).to_le_bytes());
+            }
+            stl.extend(0u16.to_le_bytes());
+        }
+        stl
+    }
+
+    /// A glTF 2.0 document and the binary buffer it refers to as `bin_uri`, usually the
+    /// name of the `.bin` file next to the `.gltf` one.
+    ///
+    /// glTF has y up, so elevations become y and the triangulation's y becomes -z.
+    /// Coordinates beyond the range of single precision are clamped to it. A mesh
+    /// without triangles gives an empty scene and an empty buffer, as glTF allows no
+    /// empty buffers or accessors.
+    pub fn to_gltf(&self, bin_uri: &str) -> (String, Vec<u8>) {
+        if self.triangles.is_empty() {
+            let gltf = r#"{
+"asset":{"version":"2.0","generator":"delaunay"},
+"scene":0,
+"scenes":[{}]
+}
+"#;
+            return (gltf.into(), Vec::new());
+        }
+
+        // subtracted rather than negated to keep zeros positive
+        let up = |[x, y, z]: [f64; 3]| [single(x), single(z), single(0.0 - y)];
+        let mut bin = Vec::new();
+        let (mut min, mut max) = ([f32::MAX; 3], [f32::MIN; 3]);
+        for &p in &self.positions {
+            let p = up(p);
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+            bin.extend(p.iter().flat_map(|x| x.to_le_bytes()));
+        }
+        let positions = bin.len();
+        for &n in &self.normals {
+            bin.extend(up(n).iter().flat_map(|x| x.to_le_bytes()));
+        }
+        let normals = bin.len() - positions;
+        for v in self.triangles.iter().flatten() {
+            bin.extend(v.to_le_bytes());
+        }
+        let indices = bin.len() - positions - normals;
+
+        let (count, index_count) = (self.positions.len(), 3 * self.triangles.len());
+        let array = |v: [f32; 3]| format!("[{},{},{}]", v[0], v[1], v[2]);
+        let uri = bin_uri.replace('\\', "\\\\").replace('"', "\\\"");
+        let gltf = format!(
+            r#"{{
+"asset":{{"version":"2.0","generator":"delaunay"}},
+"scene":0,
+"scenes":[{{"nodes":[0]}}],
+"nodes":[{{"mesh":0}}],
+"meshes":[{{"primitives":[{{"attributes":{{"POSITION":0,"NORMAL":1}},"indices":2,"mode":4}}]}}],
+"buffers":[{{"uri":"{uri}","byteLength":{}}}],
+"bufferViews":[
+{{"buffer":0,"byteOffset":0,"byteLength":{positions},"target":34962}},
+{{"buffer":0,"byteOffset":{positions},"byteLength":{normals},"target":34962}},
+{{"buffer":0,"byteOffset":{},"byteLength":{indices},"target":34963}}
+],
+"accessors":[
+{{"bufferView":0,"componentType":5126,"count":{count},"type":"VEC3","min":{},"max":{}}},
+{{"bufferView":1,"componentType":5126,"count":{count},"type":"VEC3"}},
+{{"bufferView":2,"componentType":5125,"count":{index_count},"type":"SCALAR"}}
+]
+}}
+"#,
+            bin.len(),
+            positions + normals,
+            array(min),
+            array(max),
+        );
+        (gltf, bin)
+    }
+}
+
+// x in single precision, clamped so it stays finite
+fn single(x: f64) -> f32 {
+    x.clamp(f32::MIN.into(), f32::MAX.into()) as f32
+}
+
+// the cross product of two sides, as long as twice the area
+fn face_normal([a, b, c]: [[f64; 3]; 3]) -> [f64; 3] {
+    let (u, v) = (
+        [0, 1, 2].map(|k| b[k] - a[k]),
+        [0, 1, 2].map(|k| c[k] - a[k]),
+    );
+    [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ]
+}
+
+fn normalize(n: [f64; 3]) -> [f64; 3] {
+    let length = math::sqrt(n[0] * n[0] + n[1] * n[1] + n[2] * n[2]);
+    if length > 0.0 {
+        n.map(|x| x / length)
+    } else {
+        [0.0; 3]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::triangulate;
+
+    // a right triangle with a point inside
+    fn corner() -> Triangulation {
+        triangulate(vec![(0.0, 0.0), (4.0, 0.0), (0.0, 4.0), (1.0, 1.0)]).0
+    }
+
+    #[test]
+    fn test_normals() {
+        let t = corner();
+        let flat = t.mesh(None);
+        assert!(flat.normals.iter().all(|&n| n == [0.0, 0.0, 1.0]));
+        // every triangle faces up
+        for &tri in &flat.triangles {
+            assert!(face_normal(tri.map(|v| flat.positions[v as usize]))[2] > 0.0);
+        }
+
+        // the plane z = x leans away from x
+        let z = t.points().iter().map(|p| p.x()).collect::<Vec<_>>();
+        let tilted = t.mesh(Some(&z));
+        let expected = [-1.0, 0.0, 1.0].map(|x: f64| x / 2.0f64.sqrt());
+        for n in &tilted.normals {
+            assert!((0..3).all(|k| (n[k] - expected[k]).abs() < 1e-12));
+        }
+        assert_eq!(tilted.positions[1], [4.0, 0.0, 4.0]);
+    }
+
+    #[test]
+    fn test_text_formats() {
+        let mesh = corner().mesh(None);
+        let obj = mesh.to_obj();
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 4);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("vn ")).count(), 4);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 3);
+        assert!(obj.contains("v 1 1 0\n"));
+
+        let ply = mesh.to_ply();
+        let (header, body) = ply.split_once("end_header\n").unwrap();
+        assert!(header.contains("element vertex 4\n") && header.contains("element face 3\n"));
+        assert_eq!(body.lines().count(), 4 + 3);
+        assert!(body.contains("1 1 0 0 0 1\n"));
+    }
+
+    #[test]
+    fn test_binary_formats() {
+        let mesh = corner().mesh(Some(&[0.0, 1.0, 2.0, 3.0]));
+
+        let ply = mesh.to_ply_binary();
+        let end = b"end_header\n";
+        let body = ply.windows(end.len()).position(|w| w == end).unwrap() + end.len();
+        assert_eq!(ply.len() - body, 4 * 6 * 4 + 3 * (1 + 3 * 4));
+
+        let stl = mesh.to_stl();
+        assert_eq!(stl.len(), 80 + 4 + 3 * 50);
+        assert_eq!(stl[80..84], 3u32.to_le_bytes());
+
+        let (gltf, bin) = mesh.to_gltf("corner.bin");
+        assert_eq!(bin.len(), 4 * 12 * 2 + 3 * 3 * 4);
+        assert!(gltf.contains(r#""uri":"corner.bin","byteLength":132"#));
+        assert!(gltf.contains(r#""byteOffset":96,"byteLength":36"#));
+        // elevation is up
+        assert!(gltf.contains(r#""min":[0,0,-4],"max":[4,3,0]"#));
+    }
+
+    #[test]
+    fn test_gltf_limits() {
+        let empty = Mesh {
+            positions: Vec::new(),
+            normals: Vec::new(),
+            triangles: Vec::new(),
+        };
+        let (gltf, bin) = empty.to_gltf("empty.bin");
+        assert!(bin.is_empty());
+        assert!(!gltf.contains("buffer") && !gltf.contains("accessors"));
+
+        // beyond single precision, but the bounds have to stay valid JSON numbers
+        let far = Mesh {
+            positions: vec![[0.0, 0.0, 0.0], [1e300, 0.0, 0.0], [0.0, -1e300, 0.0]],
+            normals: vec![[0.0, 0.0, 1.0]; 3],
+            triangles: vec![[0, 1, 2]],
+        };
+        let (gltf, _) = far.to_gltf("far.bin");
+        assert!(!gltf.contains("inf") && !gltf.contains("NaN"));
+        assert!(gltf.contains(&format!(r#""max":[{},0,{}]"#, f32::MAX, f32::MAX)));
+    }
+}
@@ -0,0 +1,394 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::locate::Location;
+use crate::types::{EdgeIndex, Vertex};
+use crate::util::orient2d_fast;
+use crate::Triangulation;
+
+/// Shortest paths over a triangulation used as a navigation mesh.
+///
+/// A* finds a corridor of adjacent walkable triangles, which the funnel
+/// algorithm straightens into the shortest polyline through it.
+pub struct NavMesh<'a> {
+    triangulation: &'a Triangulation,
+    walkable: Vec<bool>,
+    // vertices touching the hull or an unwalkable triangle
+    wall: Vec<bool>,
+    radius: f64,
+}
+
+// a triangle on the A* open list
+#[derive(Debug, Clone, Copy)]
+struct Open {
+    estimate: f64,
+    triangle: usize,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    // smallest estimate first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl<'a> NavMesh<'a> {
+    /// A navigation mesh on which every triangle is walkable.
+    pub fn new(triangulation: &'a Triangulation) -> Self {
+        let mut navmesh = Self {
+            triangulation,
+            walkable: vec![true; triangulation.triangles.len() / 3],
+            wall: Vec::new(),
+            radius: 0.0,
+        };
+        navmesh.find_walls();
+        navmesh
+    }
+
+    /// Restricts paths to the triangles for which `walkable` returns true.
+    pub fn walkable(mut self, walkable: impl Fn(usize) -> bool) -> Self {
+        for (t, w) in self.walkable.iter_mut().enumerate() {
+            *w = walkable(t);
+        }
+        self.find_walls();
+        self
+    }
+
+    /// The radius of the agent. Every edge a path crosses must leave room for the agent
+    /// after keeping `radius` away from each end that touches a wall, and paths keep
+    /// that distance from the corners they turn around.
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    fn find_walls(&mut self) {
+        let t = self.triangulation;
+        self.wall = vec![false; t.points.len()];
+        for e in 0..t.triangles.len() {
+            let e = EdgeIndex::from(e);
+            let open = t.half_edges[e].is_some_and(|f| self.walkable[f.get() / 3]);
+            if self.walkable[e.get() / 3] && !open {
+                self.wall[t.triangles[e]] = true;
+                self.wall[t.triangles[Triangulation::next_half_edge(e)]] = true;
+            }
+        }
+    }
+
+    /// A path from `start` to `goal`, including both, or `None` when either is off the
+    /// walkable mesh or no path fits the agent.
+    ///
+    /// The path is the shortest one through the [`corridor`](Self::corridor), which is
+    /// usually, but not always, the shortest path overall.
+    pub fn find_path(&self, start: Vertex, goal: Vertex) -> Option<Vec<Vertex>> {
+        let corridor = self.corridor(start, goal)?;
+
+        // the edges between consecutive triangles as (left, right) seen when walking through
+        let mut portals = vec![(start, start)];
+        for pair in corridor.windows(2) {
+            portals.push(self.portal(self.shared_edge(pair[0], pair[1])));
+        }
+        portals.push((goal, goal));
+
+        Some(funnel(&portals))
+    }
+
+    /// The triangles a path from `start` to `goal` passes through, in order.
+    ///
+    /// Paths are measured along the points where they cross from one triangle into
+    /// the next, taken as close to the straight line towards the goal as the
+    /// triangles allow.
+    pub fn corridor(&self, start: Vertex, goal: Vertex) -> Option<Vec<usize>> {
+        let t = self.triangulation;
+        let first = self.triangle_at(start)?;
+        let last = self.triangle_at(goal)?;
+
+        let n = self.walkable.len();
+        let mut cost = vec![f64::INFINITY; n];
+        let mut position = vec![start; n];
+        let mut from = vec![None; n];
+        let mut closed = vec![false; n];
+        let mut open = BinaryHeap::new();
+        cost[first] = 0.0;
+        open.push(Open {
+            estimate: (goal - start).length(),
+            triangle: first,
+        });
+
+        while let Some(Open { triangle, .. }) = open.pop() {
+            if triangle == last {
+                let mut corridor = vec![last];
+                let mut u = last;
+                while let Some(v) = from[u] {
+                    corridor.push(v);
+                    u = v;
+                }
+                corridor.reverse();
+                return Some(corridor);
+            }
+            if core::mem::replace(&mut closed[triangle], true) {
+                continue;
+            }
+
+            for e in (0..3).map(|k| EdgeIndex::from(3 * triangle + k)) {
+                let Some(f) = t.half_edges[e] else {
+                    continue;
+                };
+                let next = f.get() / 3;
+                if closed[next] || !self.walkable[next] {
+                    continue;
+                }
+                let (left, right) = self.portal(e);
+                if left == right && self.radius > 0.0 {
+                    continue;
+                }
+
+                // enter the next triangle where the line from this triangle's entry
+                // towards the goal crosses the portal, or at the nearer end
+                let (l, r) = (
+                    orient2d_fast(position[triangle], goal, left),
+                    orient2d_fast(position[triangle], goal, right),
+                );
+                let s = if l == r {
+                    0.5
+                } else {
+                    (l / (l - r)).clamp(0.0, 1.0)
+                };
+                let entry = left + (right - left) * s;
+                let g = cost[triangle] + (entry - position[triangle]).length();
+                if g < cost[next] {
+                    cost[next] = g;
+                    position[next] = entry;
+                    from[next] = Some(triangle);
+                    open.push(Open {
+                        estimate: g + (goal - entry).length(),
+                        triangle: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    // a walkable triangle containing p
+    fn triangle_at(&self, p: Vertex) -> Option<usize> {
+        let t = self.triangulation;
+        let candidates = match t.locate(p) {
+            Location::Triangle(triangle) => vec![triangle],
+            Location::Edge(e) => [Some(e), t.half_edges[e]]
+                .into_iter()
+                .flatten()
+                .map(|e| e.get() / 3)
+                .collect(),
+            Location::Vertex(v) => (0..t.triangles.len())
+                .filter(|&e| t.triangles[e] == v)
+                .map(|e| e / 3)
+                .collect(),
+            Location::Outside(_) => Vec::new(),
+        };
+        candidates
+            .into_iter()
+            .find(|&triangle| self.walkable[triangle])
+    }
+
+    fn shared_edge(&self, from: usize, to: usize) -> EdgeIndex {
+        let t = self.triangulation;
+        (0..3)
+            .map(|k| EdgeIndex::from(3 * from + k))
+            .find(|&e| t.half_edges[e].is_some_and(|f| f.get() / 3 == to))
+            .unwrap()
+    }
+
+    // the part of the edge leaving through e that the agent can pass, as (left, right);
+    // both ends coincide when the agent does not fit
+    fn portal(&self, e: EdgeIndex) -> (Vertex, Vertex) {
+        let t = self.triangulation;
+        let (a, b) = (
+            t.triangles[e],
+            t.triangles[Triangulation::next_half_edge(e)],
+        );
+        // triangles wind clockwise with y up, so the first vertex of an edge is on the left
+        let (mut left, mut right) = (t.points[a], t.points[b]);
+        if self.radius <= 0.0 {
+            return (left, right);
+        }
+
+        let length = (right - left).length();
+        let room = length - self.radius * (self.wall[a] as u8 + self.wall[b] as u8) as f64;
+        if room < 0.0 {
+            let middle = (left + right) / 2.0;
+            return (middle, middle);
+        }
+        let direction = (right - left) / length;
+        if self.wall[a] {
+            left = left + direction * self.radius;
+        }
+        if self.wall[b] {
+            right = right - direction * self.radius;
+        }
+        (left, right)
+    }
+}
+
+// the simple stupid funnel algorithm over (left, right) portals, the first and last
+// of which are the start and the goal
+fn funnel(portals: &[(Vertex, Vertex)]) -> Vec<Vertex> {
+    // positive when c lies to the left of a -> b with y up
+    let cross = |a: Vertex, b: Vertex, c: Vertex| -orient2d_fast(a, b, c);
+
+    let mut path = vec![portals[0].0];
+    let (mut apex, mut left, mut right) = (portals[0].0, portals[0].0, portals[0].1);
+    let (mut left_index, mut right_index) = (0, 0);
+
+    let mut i = 1;
+    while i < portals.len() {
+        let (l, r) = portals[i];
+
+        // narrow the funnel from the right, unless that crosses over the left side
+        if cross(apex, right, r) >= 0.0 {
+            if apex == right || cross(apex, left, r) < 0.0 {
+                right = r;
+                right_index = i;
+            } else {
+                if path.last() != Some(&left) {
+                    path.push(left);
+                }
+                apex = left;
+                (left, right) = (apex, apex);
+                right_index = left_index;
+                i = left_index + 1;
+                continue;
+            }
+        }
+
+        // and the same from the left
+        if cross(apex, left, l) <= 0.0 {
+            if apex == left || cross(apex, right, l) > 0.0 {
+                left = l;
+                left_index = i;
+            } else {
+                if path.last() != Some(&right) {
+                    path.push(right);
+                }
+                apex = right;
+                (left, right) = (apex, apex);
+                left_index = right_index;
+                i = right_index + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    let goal = portals[portals.len() - 1].0;
+    if path.last() != Some(&goal) {
+        path.push(goal);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate};
+
+    // a 10 x 10 grid of unit squares
+    fn grid() -> Triangulation {
+        let points =
+            generate::jittered_grid(Vertex::splat(-0.5), Vertex::splat(10.5), 11, 11, 0.0, 0);
+        triangulate(points).0
+    }
+
+    fn centroid(t: &Triangulation, triangle: usize) -> Vertex {
+        (0..3).fold(Vertex::ZERO, |c, k| {
+            c + t.points()[t.triangles()[3 * triangle + k]]
+        }) / 3.0
+    }
+
+    fn length(path: &[Vertex]) -> f64 {
+        path.windows(2).map(|w| (w[1] - w[0]).length()).sum()
+    }
+
+    #[test]
+    fn test_straight_line() {
+        let t = grid();
+        let navmesh = NavMesh::new(&t);
+        let (start, goal) = (Vertex::new(0.5, 0.3), Vertex::new(9.2, 7.7));
+        assert_eq!(navmesh.find_path(start, goal), Some(vec![start, goal]));
+        assert_eq!(navmesh.find_path(start, start), Some(vec![start]));
+        assert_eq!(navmesh.find_path(start, Vertex::new(11.0, 5.0)), None);
+    }
+
+    #[test]
+    fn test_around_obstacle() {
+        let t = grid();
+        // a wall from the bottom up to y = 8, between x = 4 and 6
+        let blocked = |p: Vertex| (4.0..6.0).contains(&p.x()) && p.y() < 8.0;
+        let navmesh = NavMesh::new(&t).walkable(|i| !blocked(centroid(&t, i)));
+
+        for (start, goal) in [((3.5, 1.0), (6.5, 1.0)), ((1.3, 1.2), (8.6, 1.1))] {
+            let (start, goal) = (Vertex::from(start), Vertex::from(goal));
+            let path = navmesh.find_path(start, goal).unwrap();
+            assert_eq!((path[0], path[path.len() - 1]), (start, goal));
+            let corners = [Vertex::new(4.0, 8.0), Vertex::new(6.0, 8.0)];
+            assert!(corners.iter().all(|c| path.contains(c)));
+            let shortest = (corners[0] - start).length() + 2.0 + (goal - corners[1]).length();
+            assert!(length(&path) < 1.05 * shortest);
+
+            // no segment cuts through the wall
+            for w in path.windows(2) {
+                assert!((0..=100).all(|k| {
+                    let p = w[0] + (w[1] - w[0]) * (k as f64 / 100.0);
+                    !(4.0 + 1e-9..6.0 - 1e-9).contains(&p.x()) || p.y() >= 8.0 - 1e-9
+                }));
+            }
+
+            let corridor = navmesh.corridor(start, goal).unwrap();
+            assert!(corridor.iter().all(|&i| !blocked(centroid(&t, i))));
+        }
+        assert_eq!(
+            navmesh.find_path(Vertex::new(3.5, 1.0), Vertex::new(5.0, 4.5)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_agent_radius() {
+        let t = grid();
+        // a wall across the middle with a gap of width 2 between y = 4 and 6
+        let blocked = |p: Vertex| (4.0..6.0).contains(&p.x()) && !(4.0..6.0).contains(&p.y());
+        let walkable = |i| !blocked(centroid(&t, i));
+        let (start, goal) = (Vertex::new(1.3, 1.2), Vertex::new(8.6, 1.1));
+
+        let path = NavMesh::new(&t)
+            .walkable(walkable)
+            .radius(0.5)
+            .find_path(start, goal)
+            .unwrap();
+        // the path passes the gap half a unit away from its lower corners
+        assert!(path.contains(&Vertex::new(4.0, 4.5)));
+        assert!(path.contains(&Vertex::new(6.0, 4.5)));
+
+        assert!(NavMesh::new(&t)
+            .walkable(walkable)
+            .radius(1.2)
+            .find_path(start, goal)
+            .is_none());
+    }
+}
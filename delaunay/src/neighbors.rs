@@ -0,0 +1,263 @@
+use alloc::collections::{BTreeSet, BinaryHeap};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::types::{EdgeIndex, VertIndex, Vertex};
+use crate::Triangulation;
+
+// a point waiting to be reported, with an edge leaving it
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f64,
+    vertex: VertIndex,
+    edge: EdgeIndex,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // closest first, ties broken by index to keep results repeatable
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then(other.vertex.cmp(&self.vertex))
+    }
+}
+
+impl Triangulation {
+    /// The point closest to `p`, found by walking downhill over Delaunay neighbors.
+    ///
+    /// Points left out of the triangulation as duplicates are never returned.
+    pub fn nearest(&self, p: Vertex) -> Option<VertIndex> {
+        self.nearest_from(p, 0).map(|(v, _)| v)
+    }
+
+    /// The `k` points closest to `p`, nearest first.
+    ///
+    /// The nearest points always form a connected piece of the triangulation,
+    /// so a best-first expansion from the nearest point finds them.
+    pub fn k_nearest(&self, p: Vertex, k: usize) -> Vec<VertIndex> {
+        self.expand(p, &mut 0, |found, _| found < k)
+    }
+
+    /// The points within distance `r` of `p`, nearest first.
+    pub fn within_radius(&self, p: Vertex, r: f64) -> Vec<VertIndex> {
+        self.expand(p, &mut 0, |_, distance| distance <= r)
+    }
+
+    /// [`nearest`](Self::nearest) for many points, each search starting
+    /// where the previous one ended, which is fast for coherent queries.
+    pub fn nearest_many(&self, points: &[Vertex]) -> Vec<Option<VertIndex>> {
+        let mut hint = 0;
+        points
+            .iter()
+            .map(|&p| {
+                let (v, e) = self.nearest_from(p, hint)?;
+                hint = e.get() / 3;
+                Some(v)
+            })
+            .collect()
+    }
+
+    /// [`k_nearest`](Self::k_nearest) for many points, see [`nearest_many`](Self::nearest_many).
+    pub fn k_nearest_many(&self, points: &[Vertex], k: usize) -> Vec<Vec<VertIndex>> {
+        self.expand_many(points, |found, _| found < k)
+    }
+
+    /// [`within_radius`](Self::within_radius) for many points,
+    /// see [`nearest_many`](Self::nearest_many).
+    pub fn within_radius_many(&self, points: &[Vertex], r: f64) -> Vec<Vec<VertIndex>> {
+        self.expand_many(points, |_, distance| distance <= r)
+    }
+
+    fn expand_many(
+        &self,
+        points: &[Vertex],
+        more: impl Fn(usize, f64) -> bool,
+    ) -> Vec<Vec<VertIndex>> {
+        let mut hint = 0;
+        points
+            .iter()
+            .map(|&p| self.expand(p, &mut hint, &more))
+            .collect()
+    }
+
+    // the nearest point to p and an edge leaving it
+    fn nearest_from(&self, p: Vertex, hint: usize) -> Option<(VertIndex, EdgeIndex)> {
+        if self.triangles.is_empty() {
+            // too few points to triangulate
+            let nearest = self.hull.iter().copied().min_by(|&a, &b| {
+                let a = self.points[a].distance_squared(p);
+                a.total_cmp(&self.points[b].distance_squared(p))
+            })?;
+            return Some((nearest, 0.into()));
+        }
+
+        let (_, mut e) = self.locate_edge(p, hint);
+        let mut v = self.triangles[e];
+        let mut distance = self.points[v].distance_squared(p);
+        // every point but the nearest has a Delaunay neighbor closer to p
+        'walk: loop {
+            for (u, f) in self.neighbors(e) {
+                let d = self.points[u].distance_squared(p);
+                if d < distance {
+                    (v, e, distance) = (u, f, d);
+                    continue 'walk;
+                }
+            }
+            return Some((v, e));
+        }
+    }
+
+    // best-first search from the nearest point while more(found so far, distance) holds;
+    // the hint moves to the triangle of the nearest point for the next search
+    fn expand(
+        &self,
+        p: Vertex,
+        hint: &mut usize,
+        more: impl Fn(usize, f64) -> bool,
+    ) -> Vec<VertIndex> {
+        let Some((vertex, edge)) = self.nearest_from(p, *hint) else {
+            return Vec::new();
+        };
+        *hint = edge.get() / 3;
+        if self.triangles.is_empty() {
+            let mut sites = self.hull.clone();
+            sites.sort_by(|&a, &b| {
+                let a = self.points[a].distance_squared(p);
+                a.total_cmp(&self.points[b].distance_squared(p))
+            });
+            return sites
+                .into_iter()
+                .enumerate()
+                .take_while(|&(k, v)| more(k, (self.points[v] - p).length()))
+                .map(|(_, v)| v)
+                .collect();
+        }
+
+        let mut found = Vec::new();
+        let mut seen = BTreeSet::from([vertex]);
+        let mut open = BinaryHeap::from([Candidate {
+            distance: (self.points[vertex] - p).length(),
+            vertex,
+            edge,
+        }]);
+        while let Some(Candidate {
+            distance,
+            vertex,
+            edge,
+        }) = open.pop()
+        {
+            if !more(found.len(), distance) {
+                break;
+            }
+            found.push(vertex);
+            for (u, f) in self.neighbors(edge) {
+                if seen.insert(u) {
+                    open.push(Candidate {
+                        distance: (self.points[u] - p).length(),
+                        vertex: u,
+                        edge: f,
+                    });
+                }
+            }
+        }
+        found
+    }
+
+    // the neighbors of the point that edge e leaves, each with an edge leaving the neighbor
+    fn neighbors(&self, e: EdgeIndex) -> Vec<(VertIndex, EdgeIndex)> {
+        let mut neighbors = Vec::new();
+        // turn one way around the point, and the other way as well if that hits the hull
+        let mut out = e;
+        loop {
+            let next = Self::next_half_edge(out);
+            neighbors.push((self.triangles[next], next));
+            let incoming = Self::prev_half_edge(out);
+            match self.half_edges[incoming] {
+                Some(twin) if twin == e => return neighbors,
+                Some(twin) => out = twin,
+                None => {
+                    neighbors.push((self.triangles[incoming], incoming));
+                    break;
+                }
+            }
+        }
+        let mut out = e;
+        while let Some(twin) = self.half_edges[out] {
+            out = Self::next_half_edge(twin);
+            let next = Self::next_half_edge(out);
+            neighbors.push((self.triangles[next], next));
+        }
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate};
+
+    fn by_distance(points: &[Vertex], p: Vertex) -> Vec<VertIndex> {
+        let mut order = (0..points.len()).map(VertIndex::from).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| {
+            let a = points[a].distance_squared(p);
+            a.total_cmp(&points[b].distance_squared(p))
+        });
+        order
+    }
+
+    #[test]
+    fn test_nearest() {
+        let points = generate::uniform(500, Vertex::ZERO, Vertex::splat(1.0), 3);
+        let (t, _) = triangulate(points.clone());
+        let queries = generate::uniform(200, Vertex::splat(-0.5), Vertex::splat(1.5), 4);
+
+        let many = t.nearest_many(&queries);
+        for (&q, &found) in queries.iter().zip(&many) {
+            let nearest = by_distance(&points, q)[0];
+            assert_eq!(t.nearest(q), Some(nearest));
+            assert_eq!(found, Some(nearest));
+        }
+        assert_eq!(t.nearest(points[17]), Some(17.into()));
+    }
+
+    #[test]
+    fn test_k_nearest_and_radius() {
+        let points = generate::jittered_grid(Vertex::ZERO, Vertex::splat(1.0), 20, 20, 0.9, 5);
+        let (t, _) = triangulate(points.clone());
+        let queries = generate::uniform(50, Vertex::splat(-0.2), Vertex::splat(1.2), 6);
+
+        let knn = t.k_nearest_many(&queries, 12);
+        let within = t.within_radius_many(&queries, 0.15);
+        for (i, &q) in queries.iter().enumerate() {
+            let order = by_distance(&points, q);
+            assert_eq!(t.k_nearest(q, 12), order[..12]);
+            assert_eq!(knn[i], order[..12]);
+
+            let inside = order
+                .iter()
+                .copied()
+                .take_while(|&v| (points[v] - q).length() <= 0.15)
+                .collect::<Vec<_>>();
+            assert_eq!(t.within_radius(q, 0.15), inside);
+            assert_eq!(within[i], inside);
+        }
+
+        assert_eq!(t.k_nearest(queries[0], 1000).len(), 400);
+        assert!(t.within_radius(Vertex::splat(5.0), 1.0).is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::types::{EdgeIndex, VertIndex};
+
+/// A step of [`Triangulation::update_observed`](crate::Triangulation::update_observed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The sweep starts from the triangle on these points.
+    Seed([VertIndex; 3]),
+    /// The point is left out as a duplicate of another.
+    Duplicate { point: VertIndex, of: VertIndex },
+    /// A triangle on these points was written to the slots from half-edge `first` on.
+    Triangle {
+        first: EdgeIndex,
+        corners: [VertIndex; 3],
+    },
+    /// The diagonal shared by half-edges `edge` and `twin` was flipped.
+    Flip { edge: EdgeIndex, twin: EdgeIndex },
+    /// The point was joined to the triangulation.
+    Inserted(VertIndex),
+    /// The hull now runs from `start` through `size` points.
+    Hull { start: VertIndex, size: usize },
+}
+
+/// One line of JSON per event, as in [`Recorder::to_json`].
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Event::Seed([a, b, c]) => write!(
+                f,
+                r#"{{"event":"seed","points":[{},{},{}]}}"#,
+                a.get(),
+                b.get(),
+                c.get()
+            ),
+            Event::Duplicate { point, of } => write!(
+                f,
+                r#"{{"event":"duplicate","point":{},"of":{}}}"#,
+                point.get(),
+                of.get()
+            ),
+            Event::Triangle {
+                first,
+                corners: [a, b, c],
+            } => write!(
+                f,
+                r#"{{"event":"triangle","edge":{},"points":[{},{},{}]}}"#,
+                first.get(),
+                a.get(),
+                b.get(),
+                c.get()
+            ),
+            Event::Flip { edge, twin } => write!(
+                f,
+                r#"{{"event":"flip","edge":{},"twin":{}}}"#,
+                edge.get(),
+                twin.get()
+            ),
+            Event::Inserted(point) => {
+                write!(f, r#"{{"event":"insert","point":{}}}"#, point.get())
+            }
+            Event::Hull { start, size } => write!(
+                f,
+                r#"{{"event":"hull","start":{},"size":{size}}}"#,
+                start.get()
+            ),
+        }
+    }
+}
+
+/// Watches the steps of an update, for teaching or for replaying a degenerate case.
+///
+/// The update is generic over the observer, so `()`, which ignores every event,
+/// compiles down to the update without one.
+pub trait Observer {
+    fn observe(&mut self, event: Event);
+}
+
+impl Observer for () {
+    #[inline(always)]
+    fn observe(&mut self, _: Event) {}
+}
+
+/// An observer that keeps every event in order.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    events: Vec<Event>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// The log as a JSON array with one event object per line.
+    pub fn to_json(&self) -> String {
+        if self.events.is_empty() {
+            return "[]\n".to_string();
+        }
+        let lines = self
+            .events
+            .iter()
+            .map(|event| format!("  {event}"))
+            .collect::<Vec<_>>();
+        format!("[\n{}\n]\n", lines.join(",\n"))
+    }
+}
+
+impl Observer for Recorder {
+    fn observe(&mut self, event: Event) {
+        self.events.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate, Progress, Vertex};
+
+    #[test]
+    fn test_recorded_events() {
+        let mut points = generate::uniform(200, Vertex::ZERO, Vertex::splat(1.0), 21);
+        points.push(points[7]);
+        let (mut t, mut hull) = triangulate(points);
+        let triangles = t.triangles().to_vec();
+
+        let mut recorder = Recorder::new();
+        t.update_observed(&mut hull, &mut Progress::new(), &mut recorder)
+            .unwrap();
+        assert_eq!(t.triangles(), triangles);
+
+        let events = recorder.events();
+        let count = |f: fn(&Event) -> bool| events.iter().filter(|e| f(e)).count();
+        assert!(matches!(events[0], Event::Seed(_)));
+        // the duplicate is found when the sweep reaches it
+        let duplicate = Event::Duplicate {
+            point: 200.into(),
+            of: 7.into(),
+        };
+        assert_eq!(count(|e| matches!(e, Event::Duplicate { .. })), 1);
+        assert!(events.contains(&duplicate));
+        assert_eq!(count(|e| matches!(e, Event::Inserted(_))), 197);
+        assert_eq!(count(|e| matches!(e, Event::Hull { .. })), 197);
+        assert!(count(|e| matches!(e, Event::Flip { .. })) > 0);
+
+        // flips reuse the slots of the triangles they replace, so every one added remains
+        let added = count(|e| matches!(e, Event::Triangle { .. }));
+        assert_eq!(added * 3, triangles.len());
+        let Some(&Event::Hull { size, .. }) = events.last() else {
+            panic!("the last event updates the hull");
+        };
+        assert_eq!(size, t.hull().len());
+    }
+
+    #[test]
+    fn test_json() {
+        let mut recorder = Recorder::new();
+        recorder.observe(Event::Seed([0.into(), 2.into(), 1.into()]));
+        recorder.observe(Event::Flip {
+            edge: 4.into(),
+            twin: 9.into(),
+        });
+        assert_eq!(
+            recorder.to_json(),
+            "[\n  {\"event\":\"seed\",\"points\":[0,2,1]},\n  {\"event\":\"flip\",\"edge\":4,\"twin\":9}\n]\n"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::generate::Rng;
+use crate::types::{VertIndex, Vertex};
+
+// cells per side of the grid that points are snapped to before sorting
+const BITS: u32 = 16;
+
+/// The points along a Hilbert curve through their bounding box.
+pub(crate) fn hilbert(points: &[Vertex]) -> Vec<VertIndex> {
+    sort_by_key(points, hilbert_key)
+}
+
+/// The points in Z-order, by interleaving the bits of their coordinates.
+pub(crate) fn morton(points: &[Vertex]) -> Vec<VertIndex> {
+    sort_by_key(points, |x, y| spread(x) | spread(y) << 1)
+}
+
+/// Biased randomized insertion order: rounds of growing size, each point landing in the
+/// last round with probability 1/2, the one before with 1/4 and so on, and every round
+/// in Hilbert order. Random enough for the expected bounds of randomized insertion,
+/// local enough for point location and the cache.
+pub(crate) fn brio(points: &[Vertex], seed: u64) -> Vec<VertIndex> {
+    let mut rng = Rng::new(seed);
+    let rounds = usize::BITS - points.len().leading_zeros();
+    let round = points
+        .iter()
+        .map(|_| Reverse(rng.next_u64().trailing_zeros().min(rounds)))
+        .collect::<Vec<_>>();
+    let keys = grid(points)
+        .map(|(x, y)| hilbert_key(x, y))
+        .collect::<Vec<_>>();
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| (round[i], keys[i]));
+    order.into_iter().map(VertIndex::from).collect()
+}
+
+fn sort_by_key(points: &[Vertex], key: impl Fn(u32, u32) -> u64) -> Vec<VertIndex> {
+    let keys = grid(points).map(|(x, y)| key(x, y)).collect::<Vec<_>>();
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| keys[i]);
+    order.into_iter().map(VertIndex::from).collect()
+}
+
+// the points snapped to a square grid over their bounding box
+fn grid(points: &[Vertex]) -> impl Iterator<Item = (u32, u32)> + '_ {
+    let (min, max) = points.iter().filter(|p| !p.is_nan()).fold(
+        (Vertex::INFINITY, Vertex::INFINITY * -1.0),
+        |(min, max), &p| (min.min(p), max.max(p)),
+    );
+    let size = (max.x() - min.x()).max(max.y() - min.y());
+    let scale = if size > 0.0 {
+        ((1 << BITS) - 1) as f64 / size
+    } else {
+        0.0
+    };
+    points.iter().map(move |&p| {
+        let cell = (p - min) * scale;
+        (cell.x() as u32, cell.y() as u32)
+    })
+}
+
+// the distance along the Hilbert curve filling the grid
+fn hilbert_key(mut x: u32, mut y: u32) -> u64 {
+    let n = 1 << BITS;
+    let mut d = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = u32::from(x & s > 0);
+        let ry = u32::from(y & s > 0);
+        d += u64::from(s) * u64::from(s) * u64::from((3 * rx) ^ ry);
+        // rotate the quadrant so the curve inside it starts and ends in the right corners
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            core::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+// the bits of v spaced out to every other bit
+fn spread(v: u32) -> u64 {
+    let mut v = u64::from(v);
+    v = (v | v << 16) & 0x0000_ffff_0000_ffff;
+    v = (v | v << 8) & 0x00ff_00ff_00ff_00ff;
+    v = (v | v << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+    v = (v | v << 2) & 0x3333_3333_3333_3333;
+    (v | v << 1) & 0x5555_5555_5555_5555
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    fn path_length(points: &[Vertex], order: &[VertIndex]) -> f64 {
+        order
+            .windows(2)
+            .map(|w| (points[w[0]] - points[w[1]]).length())
+            .sum()
+    }
+
+    #[test]
+    fn test_orders_are_permutations() {
+        let points = generate::uniform(1000, Vertex::ZERO, Vertex::splat(1.0), 1);
+        for order in [hilbert(&points), morton(&points), brio(&points, 2)] {
+            let mut sorted = order.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..1000).map(VertIndex::from).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_curves_are_local() {
+        let points = generate::uniform(4000, Vertex::ZERO, Vertex::splat(1.0), 3);
+        let input = (0..4000).map(VertIndex::from).collect::<Vec<_>>();
+        let random = path_length(&points, &input);
+        assert!(path_length(&points, &hilbert(&points)) < random / 20.0);
+        assert!(path_length(&points, &morton(&points)) < random / 10.0);
+
+        // each quarter of the curve fills one quadrant
+        let grid = (0..16 * 16)
+            .map(|k| Vertex::new((k % 16) as f64, (k / 16) as f64))
+            .collect::<Vec<_>>();
+        let order = hilbert(&grid);
+        let quadrant = |v: VertIndex| (grid[v].x() < 8.0, grid[v].y() < 8.0);
+        for k in 0..4 {
+            let part = &order[64 * k..64 * (k + 1)];
+            assert!(part.iter().all(|&v| quadrant(v) == quadrant(part[0])));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Adaptive exact versions of the orientation and incircle tests, after Shewchuk's
+//! "Adaptive Precision Floating-Point Arithmetic and Fast Robust Geometric Predicates".
+//!
+//! The floating-point result is used whenever its error bound proves the sign right;
+//! otherwise the determinant is evaluated exactly as an expansion, a sum of
+//! non-overlapping doubles kept in increasing order of magnitude.
+
+use alloc::vec::Vec;
+
+use crate::math;
+use crate::types::Vertex;
+use crate::util::{in_circle, orient2d_fast};
+use crate::Triangulation;
+
+// half an ulp of 1.0, the relative error of a single rounding
+const EPSILON: f64 = f64::EPSILON / 2.0;
+const ORIENT_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const INCIRCLE_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+/// The sign of [`orient2d_fast`], exact for every input.
+pub(crate) fn orient2d(a: Vertex, b: Vertex, c: Vertex) -> f64 {
+    let left = (a.y() - c.y()) * (b.x() - c.x());
+    let right = (a.x() - c.x()) * (b.y() - c.y());
+    let det = left - right;
+    if det.abs() > ORIENT_BOUND * (left.abs() + right.abs()) {
+        return det;
+    }
+
+    // the cx * cy terms cancel
+    let terms = [
+        (a.y(), b.x()),
+        (-a.y(), c.x()),
+        (-c.y(), b.x()),
+        (-a.x(), b.y()),
+        (a.x(), c.y()),
+        (c.x(), b.y()),
+    ];
+    let det = terms.iter().fold(Vec::new(), |sum, &(u, v)| {
+        let (product, error) = two_product(u, v);
+        grow(&grow(&sum, error), product)
+    });
+    most_significant(&det)
+}
+
+/// [`in_circle`], exact for every input.
+pub(crate) fn in_circle_exact(a: Vertex, b: Vertex, c: Vertex, p: Vertex) -> bool {
+    let (d, e, f) = (a - p, b - p, c - p);
+    let (ap, bp, cp) = (d.length_squared(), e.length_squared(), f.length_squared());
+    let det = d.x() * (e.y() * cp - bp * f.y()) - d.y() * (e.x() * cp - bp * f.x())
+        + ap * (e.x() * f.y() - e.y() * f.x());
+    let permanent = ((e.y() * cp).abs() + (bp * f.y()).abs()) * d.x().abs()
+        + ((e.x() * cp).abs() + (bp * f.x()).abs()) * d.y().abs()
+        + ((e.x() * f.y()).abs() + (e.y() * f.x()).abs()) * ap;
+    if det.abs() > INCIRCLE_BOUND * permanent {
+        return det < 0.0;
+    }
+
+    // the same determinant with every difference and product kept exact
+    let difference = |u: f64, v: f64| grow(&[u], -v);
+    let (dx, dy) = (difference(a.x(), p.x()), difference(a.y(), p.y()));
+    let (ex, ey) = (difference(b.x(), p.x()), difference(b.y(), p.y()));
+    let (fx, fy) = (difference(c.x(), p.x()), difference(c.y(), p.y()));
+    let lift = |x: &[f64], y: &[f64]| sum(&product(x, x), &product(y, y));
+    let (ap, bp, cp) = (lift(&dx, &dy), lift(&ex, &ey), lift(&fx, &fy));
+
+    let cross =
+        |u: &[f64], v: &[f64], w: &[f64], z: &[f64]| sum(&product(u, v), &negate(&product(w, z)));
+    let det = sum(
+        &sum(
+            &product(&dx, &cross(&ey, &cp, &bp, &fy)),
+            &negate(&product(&dy, &cross(&ex, &cp, &bp, &fx))),
+        ),
+        &product(&ap, &cross(&ex, &fy, &ey, &fx)),
+    );
+    most_significant(&det) < 0.0
+}
+
+impl Triangulation {
+    // the orientation test, exact when the triangulation is robust
+    pub(crate) fn orient(&self, a: Vertex, b: Vertex, c: Vertex) -> f64 {
+        if self.robust {
+            orient2d(a, b, c)
+        } else {
+            orient2d_fast(a, b, c)
+        }
+    }
+
+    // the incircle test, exact when the triangulation is robust
+    pub(crate) fn in_circumcircle(&self, a: Vertex, b: Vertex, c: Vertex, p: Vertex) -> bool {
+        if self.robust {
+            in_circle_exact(a, b, c, p)
+        } else {
+            in_circle(a, b, c, p)
+        }
+    }
+}
+
+// a + b as the rounded sum and its rounding error
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    (x, (a - a_virtual) + (b - b_virtual))
+}
+
+// a * b as the rounded product and its rounding error
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    (x, math::mul_add(a, b, -x))
+}
+
+// the expansion e + b, dropping zero components
+fn grow(e: &[f64], b: f64) -> Vec<f64> {
+    let mut grown = Vec::with_capacity(e.len() + 1);
+    let mut q = b;
+    for &component in e {
+        let (s, error) = two_sum(q, component);
+        if error != 0.0 {
+            grown.push(error);
+        }
+        q = s;
+    }
+    if q != 0.0 || grown.is_empty() {
+        grown.push(q);
+    }
+    grown
+}
+
+fn sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter()
+        .fold(e.to_vec(), |sum, &component| grow(&sum, component))
+}
+
+fn negate(e: &[f64]) -> Vec<f64> {
+    e.iter().map(|&component| -component).collect()
+}
+
+fn product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let mut total = Vec::new();
+    for &b in f {
+        for &a in e {
+            let (product, error) = two_product(a, b);
+            total = grow(&grow(&total, error), product);
+        }
+    }
+    total
+}
+
+// the largest component, which carries the sign of the whole expansion
+fn most_significant(e: &[f64]) -> f64 {
+    e.last().copied().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orient_near_collinear() {
+        // points a hair off the line y = x, far beyond what the fast test resolves
+        let (a, b) = (Vertex::new(12.0, 12.0), Vertex::new(24.0, 24.0));
+        for k in 1..128 {
+            let x = 0.5 + k as f64 * f64::EPSILON;
+            let c = Vertex::new(x, 0.5);
+            assert_eq!(orient2d(a, b, c).partial_cmp(&0.0), Some(x.total_cmp(&0.5)));
+            assert_eq!(orient2d(a, b, c).signum(), -orient2d(b, a, c).signum());
+        }
+        assert_eq!(orient2d(a, b, Vertex::new(0.5, 0.5)), 0.0);
+    }
+
+    #[test]
+    fn test_in_circle_cocircular() {
+        // points on the unit circle are neither inside nor outside each other's circle
+        let (a, b, c) = (
+            Vertex::new(1.0, 0.0),
+            Vertex::new(0.0, -1.0),
+            Vertex::new(-1.0, 0.0),
+        );
+        assert!(!in_circle_exact(a, b, c, Vertex::new(0.0, 1.0)));
+        assert!(in_circle_exact(
+            a,
+            b,
+            c,
+            Vertex::new(0.0, 1.0 - f64::EPSILON)
+        ));
+        assert!(!in_circle_exact(
+            a,
+            b,
+            c,
+            Vertex::new(0.0, 1.0 + f64::EPSILON)
+        ));
+        for k in 0..20 {
+            let angle = k as f64 * 0.3;
+            let p = Vertex::new(angle.cos(), angle.sin()) * 0.5;
+            assert_eq!(in_circle_exact(a, b, c, p), in_circle(a, b, c, p));
+        }
+    }
+}
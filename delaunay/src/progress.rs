@@ -0,0 +1,185 @@
+use alloc::boxed::Box;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::vec;
+use core::fmt;
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Triangulation;
+
+/// A flag to stop a triangulation from another thread or a callback.
+/// Clones share the flag. Only on targets with pointer-sized atomics, which `Arc` needs.
+#[cfg(target_has_atomic = "ptr")]
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+#[cfg(target_has_atomic = "ptr")]
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every run watching this token to stop at its next check.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The run was stopped through its [`CancelToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the triangulation was cancelled")
+    }
+}
+
+impl core::error::Error for Cancelled {}
+
+/// What a long update reports to, and when it should give up.
+///
+/// Every `interval` points the callback is called with the number of points processed
+/// so far and the total, and then the token is checked. The callback also sees the
+/// total once more when the run is done.
+pub struct Progress<'a> {
+    report: Option<Box<dyn FnMut(usize, usize) + 'a>>,
+    #[cfg(target_has_atomic = "ptr")]
+    cancel: Option<CancelToken>,
+    interval: usize,
+}
+
+impl fmt::Debug for Progress<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Progress");
+        #[cfg(target_has_atomic = "ptr")]
+        debug.field("cancel", &self.cancel);
+        debug
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Progress<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Progress<'a> {
+    /// Checks every 1024 points, with no callback and no token.
+    pub fn new() -> Self {
+        Self {
+            report: None,
+            #[cfg(target_has_atomic = "ptr")]
+            cancel: None,
+            interval: 1024,
+        }
+    }
+
+    pub fn report(mut self, report: impl FnMut(usize, usize) + 'a) -> Self {
+        self.report = Some(Box::new(report));
+        self
+    }
+
+    #[cfg(target_has_atomic = "ptr")]
+    pub fn cancel(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// How many points are processed between checks, at least one.
+    pub fn interval(mut self, points: usize) -> Self {
+        self.interval = points.max(1);
+        self
+    }
+
+    // reports and checks the token if `processed` is due
+    pub(crate) fn check(&mut self, processed: usize, total: usize) -> Result<(), Cancelled> {
+        if !processed.is_multiple_of(self.interval) {
+            return Ok(());
+        }
+        if let Some(report) = &mut self.report {
+            report(processed, total);
+        }
+        #[cfg(target_has_atomic = "ptr")]
+        if self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+            return Err(Cancelled);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self, total: usize) {
+        if let Some(report) = &mut self.report {
+            report(total, total);
+        }
+    }
+}
+
+impl Triangulation {
+    // drops whatever a cancelled run built so far
+    pub(crate) fn abandon(&mut self) -> Cancelled {
+        self.triangles.clear();
+        self.half_edges.clear();
+        self.hull.clear();
+        if self.vertex_index.is_some() {
+            self.vertex_index = Some(vec![None; self.points.len()]);
+        }
+        Cancelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{generate, triangulate, Vertex};
+
+    #[test]
+    fn test_progress_reports() {
+        let points = generate::uniform(5000, Vertex::ZERO, Vertex::splat(1.0), 8);
+        let (mut t, mut hull) = triangulate(points);
+        let triangles = t.triangles().to_vec();
+
+        let mut calls = Vec::new();
+        let mut progress = Progress::new()
+            .interval(1000)
+            .report(|k, n| calls.push((k, n)));
+        t.update_with_progress(&mut hull, &mut progress).unwrap();
+        drop(progress);
+        assert_eq!(t.triangles(), triangles);
+        assert_eq!(calls.first(), Some(&(0, 5000)));
+        assert_eq!(calls.last(), Some(&(5000, 5000)));
+        assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_cancel_midway() {
+        let points = generate::uniform(5000, Vertex::ZERO, Vertex::splat(1.0), 9);
+        let (mut t, mut hull) = triangulate(points);
+        let token = CancelToken::new();
+        let stop = token.clone();
+        let mut progress = Progress::new()
+            .interval(100)
+            .cancel(token)
+            .report(move |k, _| {
+                if k >= 2000 {
+                    stop.cancel();
+                }
+            });
+        assert_eq!(
+            t.update_with_progress(&mut hull, &mut progress),
+            Err(Cancelled)
+        );
+        assert!(t.triangles().is_empty() && t.hull().is_empty());
+
+        t.update_with(&mut hull);
+        assert_eq!(t.validate(), Ok(()));
+    }
+}
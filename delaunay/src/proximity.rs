@@ -0,0 +1,251 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::hull::Circle;
+use crate::types::{EdgeIndex, VertIndex, Vertex};
+use crate::util::{circumcenter, point_in_polygon};
+use crate::Triangulation;
+
+// a piece of a Voronoi edge, origin + direction * t for t in range,
+// between the cells of two sites
+struct Bisector {
+    site: Vertex,
+    origin: Vertex,
+    direction: Vertex,
+    range: (f64, f64),
+}
+
+impl Bisector {
+    // where the bisector crosses the segment a-b, if it does
+    fn crossing(&self, a: Vertex, b: Vertex) -> Option<Vertex> {
+        let cross = |u: Vertex, v: Vertex| u.x() * v.y() - u.y() * v.x();
+        let edge = b - a;
+        let denominator = cross(self.direction, edge);
+        if denominator == 0.0 {
+            return None;
+        }
+        let offset = a - self.origin;
+        let t = cross(offset, edge) / denominator;
+        let s = cross(offset, self.direction) / denominator;
+        ((self.range.0..=self.range.1).contains(&t) && (0.0..=1.0).contains(&s))
+            .then(|| a + edge * s)
+    }
+}
+
+impl Triangulation {
+    /// The two closest points, the ends of the shortest Delaunay edge.
+    ///
+    /// Points left out of the triangulation as duplicates are not considered.
+    pub fn closest_pair(&self) -> Option<(VertIndex, VertIndex)> {
+        self.neighbor_pairs().min_by(|&(a, b), &(c, d)| {
+            let ab = self.points[a].distance_squared(self.points[b]);
+            ab.total_cmp(&self.points[c].distance_squared(self.points[d]))
+        })
+    }
+
+    /// The nearest other point of every point, which is always one of its Delaunay neighbors.
+    ///
+    /// Points left out of the triangulation as duplicates have no neighbor.
+    pub fn all_nearest_neighbors(&self) -> Vec<Option<VertIndex>> {
+        let mut nearest = vec![None; self.points.len()];
+        let mut distance = vec![f64::INFINITY; self.points.len()];
+        for (a, b) in self.neighbor_pairs() {
+            let d = self.points[a].distance_squared(self.points[b]);
+            for (from, to) in [(a, b), (b, a)] {
+                if d < distance[from] {
+                    distance[from] = d;
+                    nearest[from] = Some(to);
+                }
+            }
+        }
+        nearest
+    }
+
+    /// The largest circle without points inside whose center lies within the convex hull.
+    pub fn largest_empty_circle(&self) -> Option<Circle> {
+        self.largest_empty_circle_in(&self.hull_points())
+    }
+
+    /// The largest circle without points inside whose center lies within `polygon`,
+    /// which may have either winding and need not be convex.
+    ///
+    /// The center is a Voronoi vertex, a crossing of a Voronoi edge with the boundary
+    /// of the polygon, or a corner of the polygon.
+    pub fn largest_empty_circle_in(&self, polygon: &[Vertex]) -> Option<Circle> {
+        if self.hull.is_empty() || polygon.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<Circle> = None;
+        let mut consider = |center: Vertex, radius: f64| {
+            if best.is_none_or(|best| radius > best.radius) {
+                best = Some(Circle { center, radius });
+            }
+        };
+
+        for t in self.triangles.chunks_exact(3) {
+            let center = circumcenter(self.points[t[0]], self.points[t[1]], self.points[t[2]]);
+            if point_in_polygon(center, polygon) {
+                consider(center, (self.points[t[0]] - center).length());
+            }
+        }
+
+        let n = polygon.len();
+        for bisector in self.bisectors() {
+            for i in 0..n {
+                if let Some(center) = bisector.crossing(polygon[i], polygon[(i + 1) % n]) {
+                    consider(center, (bisector.site - center).length());
+                }
+            }
+        }
+
+        for &corner in polygon {
+            if let Some(nearest) = self.nearest(corner) {
+                consider(corner, (self.points[nearest] - corner).length());
+            }
+        }
+        best
+    }
+
+    // every Delaunay edge once, or consecutive points when they are all on a line
+    fn neighbor_pairs(&self) -> Box<dyn Iterator<Item = (VertIndex, VertIndex)> + '_> {
+        if self.triangles.is_empty() {
+            return Box::new(self.hull.windows(2).map(|w| (w[0], w[1])));
+        }
+        Box::new(
+            (0..self.triangles.len())
+                .map(EdgeIndex::from)
+                .filter(|&e| self.half_edges[e].is_none_or(|twin| e > twin))
+                .map(|e| (self.triangles[e], self.triangles[Self::next_half_edge(e)])),
+        )
+    }
+
+    fn bisectors(&self) -> Vec<Bisector> {
+        let perpendicular = |a: Vertex, b: Vertex| Vertex::new(a.y() - b.y(), b.x() - a.x());
+
+        if self.triangles.is_empty() {
+            // collinear points split the plane with parallel lines
+            return self
+                .hull
+                .windows(2)
+                .map(|w| {
+                    let (a, b) = (self.points[w[0]], self.points[w[1]]);
+                    Bisector {
+                        site: a,
+                        origin: (a + b) / 2.0,
+                        direction: perpendicular(a, b),
+                        range: (f64::NEG_INFINITY, f64::INFINITY),
+                    }
+                })
+                .collect();
+        }
+
+        let center = |e: EdgeIndex| {
+            let t = e.get() - e.get() % 3;
+            circumcenter(
+                self.points[self.triangles[t]],
+                self.points[self.triangles[t + 1]],
+                self.points[self.triangles[t + 2]],
+            )
+        };
+        (0..self.triangles.len())
+            .map(EdgeIndex::from)
+            .filter(|&e| self.half_edges[e].is_none_or(|twin| e > twin))
+            .map(|e| {
+                let (a, b) = (
+                    self.points[self.triangles[e]],
+                    self.points[self.triangles[Self::next_half_edge(e)]],
+                );
+                let origin = center(e);
+                match self.half_edges[e] {
+                    Some(twin) => Bisector {
+                        site: a,
+                        origin,
+                        direction: center(twin) - origin,
+                        range: (0.0, 1.0),
+                    },
+                    // the triangle lies to the right of a hull edge, so the
+                    // edge's cell boundary runs off to the left
+                    None => Bisector {
+                        site: a,
+                        origin,
+                        direction: perpendicular(a, b),
+                        range: (0.0, f64::INFINITY),
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate};
+
+    #[test]
+    fn test_closest_pair() {
+        for seed in 0..5 {
+            let points = generate::uniform(300, Vertex::ZERO, Vertex::splat(1.0), seed);
+            let (t, _) = triangulate(points.clone());
+
+            let nearest = t.all_nearest_neighbors();
+            for (i, &p) in points.iter().enumerate() {
+                let brute = (0..points.len())
+                    .filter(|&j| j != i)
+                    .min_by(|&a, &b| {
+                        p.distance_squared(points[a])
+                            .total_cmp(&p.distance_squared(points[b]))
+                    })
+                    .unwrap();
+                assert_eq!(nearest[i], Some(brute.into()));
+            }
+
+            let (a, b) = t.closest_pair().unwrap();
+            let d = points[a].distance_squared(points[b]);
+            assert!(points
+                .iter()
+                .enumerate()
+                .all(|(i, &p)| nearest[i].is_some_and(|j| p.distance_squared(points[j]) >= d)));
+        }
+    }
+
+    #[test]
+    fn test_largest_empty_circle() {
+        let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (1.0, 1.0)];
+        let (t, _) = triangulate(square.to_vec());
+        let circle = t.largest_empty_circle().unwrap();
+        assert!((circle.radius - 1.0).abs() < 1e-12);
+
+        // far from the center site, the best spot is the corner of the region
+        let (t, _) = triangulate(square.map(|(x, y)| (5.0 * x, 5.0 * y)).to_vec());
+        let region = [(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)].map(Vertex::from);
+        let circle = t.largest_empty_circle_in(&region).unwrap();
+        assert_eq!(circle.center, Vertex::new(2.0, 2.0));
+        assert!((circle.radius - 8f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_largest_empty_circle_is_empty() {
+        let points = generate::uniform(100, Vertex::ZERO, Vertex::splat(1.0), 7);
+        let (t, _) = triangulate(points.clone());
+        let circle = t.largest_empty_circle().unwrap();
+        assert!(t.hull_contains(circle.center));
+        assert!(points
+            .iter()
+            .all(|&p| (p - circle.center).length() >= circle.radius * (1.0 - 1e-9)));
+
+        // no point of the hull is further from every site
+        let hull = t.hull_points();
+        for sample in generate::uniform(2000, Vertex::ZERO, Vertex::splat(1.0), 8) {
+            if point_in_polygon(sample, &hull) {
+                let gap = points
+                    .iter()
+                    .map(|&p| (p - sample).length())
+                    .fold(f64::INFINITY, f64::min);
+                assert!(gap <= circle.radius + 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::math;
+use crate::types::{EdgeIndex, Vertex};
+use crate::util::{circumradius, polygon_area};
+use crate::Triangulation;
+
+/// Shape measures of a single triangle. Angles are in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleQuality {
+    pub min_angle: f64,
+    pub max_angle: f64,
+    /// Longest edge over the shortest altitude, scaled so an equilateral triangle scores 1.
+    pub aspect_ratio: f64,
+    /// Circumradius over the shortest edge, `1 / sqrt(3)` for an equilateral triangle.
+    pub radius_edge_ratio: f64,
+    pub area: f64,
+}
+
+impl TriangleQuality {
+    pub fn new(a: Vertex, b: Vertex, c: Vertex) -> Self {
+        let lengths = [
+            b.distance_squared(c),
+            c.distance_squared(a),
+            a.distance_squared(b),
+        ]
+        .map(math::sqrt);
+        let area = polygon_area(&[a, b, c]).abs();
+
+        // law of cosines, clamped against rounding for needle-like triangles
+        let angle = |i: usize| {
+            let (opposite, u, v) = (lengths[i], lengths[(i + 1) % 3], lengths[(i + 2) % 3]);
+            math::acos(((u * u + v * v - opposite * opposite) / (2.0 * u * v)).clamp(-1.0, 1.0))
+                .to_degrees()
+        };
+        let angles = [angle(0), angle(1), angle(2)];
+
+        let shortest = lengths.iter().copied().fold(f64::INFINITY, f64::min);
+        let longest = lengths.iter().copied().fold(0.0, f64::max);
+
+        Self {
+            min_angle: angles.iter().copied().fold(f64::INFINITY, f64::min),
+            max_angle: angles.iter().copied().fold(0.0, f64::max),
+            aspect_ratio: longest * longest * math::sqrt(3.0) / (4.0 * area),
+            radius_edge_ratio: math::sqrt(circumradius(a, b, c)) / shortest,
+            area,
+        }
+    }
+}
+
+/// Counts of values in equally wide bins between `min` and `max`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub min: f64,
+    pub max: f64,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Bins `values` over their own range. The maximum falls into the last bin.
+    pub fn new(values: &[f64], bins: usize) -> Self {
+        let (min, max) = values
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        let mut counts = vec![0; bins];
+        if bins > 0 && !values.is_empty() {
+            let width = (max - min) / bins as f64;
+            for &v in values {
+                let i = if width > 0.0 {
+                    ((v - min) / width) as usize
+                } else {
+                    0
+                };
+                counts[i.min(bins - 1)] += 1;
+            }
+        }
+        Self { min, max, counts }
+    }
+
+    pub fn bin_width(&self) -> f64 {
+        (self.max - self.min) / self.counts.len() as f64
+    }
+
+    /// The lower bound and count of every bin.
+    pub fn bins(&self) -> impl Iterator<Item = (f64, usize)> + '_ {
+        let width = self.bin_width();
+        self.counts
+            .iter()
+            .enumerate()
+            .map(move |(i, &c)| (self.min + width * i as f64, c))
+    }
+}
+
+/// The range, mean and histogram of one measure over a mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub histogram: Histogram,
+}
+
+impl Distribution {
+    pub fn new(values: &[f64], bins: usize) -> Self {
+        let histogram = Histogram::new(values, bins);
+        Self {
+            min: histogram.min,
+            max: histogram.max,
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            histogram,
+        }
+    }
+}
+
+/// Quality statistics of a whole triangulation, see [`Triangulation::quality_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityReport {
+    pub triangles: usize,
+    pub min_angle: Distribution,
+    pub max_angle: Distribution,
+    pub aspect_ratio: Distribution,
+    pub radius_edge_ratio: Distribution,
+    pub area: Distribution,
+    /// Lengths of the undirected edges, each counted once.
+    pub edge_length: Distribution,
+    /// Triangle indices ordered from the smallest minimum angle up.
+    pub worst: Vec<usize>,
+}
+
+impl Triangulation {
+    /// The quality of triangle `t`, whose corners are `triangles[3 * t..3 * t + 3]`.
+    pub fn triangle_quality(&self, t: usize) -> TriangleQuality {
+        let [a, b, c] = [0, 1, 2].map(|i| self.points[self.triangles[3 * t + i]]);
+        TriangleQuality::new(a, b, c)
+    }
+
+    /// Summarizes the mesh with `bins` histogram bins per measure and
+    /// lists the `worst` triangles with the smallest angles.
+    pub fn quality_report(&self, bins: usize, worst: usize) -> QualityReport {
+        let qualities = (0..self.triangles.len() / 3)
+            .map(|t| self.triangle_quality(t))
+            .collect::<Vec<_>>();
+        let distribution = |measure: fn(&TriangleQuality) -> f64| {
+            Distribution::new(&qualities.iter().map(measure).collect::<Vec<_>>(), bins)
+        };
+
+        let edge_lengths = (0..self.triangles.len())
+            .map(EdgeIndex::from)
+            .filter(|&e| self.half_edges[e].is_none_or(|twin| e.get() < twin.get()))
+            .map(|e| {
+                let a = self.points[self.triangles[e]];
+                let b = self.points[self.triangles[Self::next_half_edge(e)]];
+                math::sqrt(a.distance_squared(b))
+            })
+            .collect::<Vec<_>>();
+
+        let mut order = (0..qualities.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| qualities[a].min_angle.total_cmp(&qualities[b].min_angle));
+        order.truncate(worst);
+
+        QualityReport {
+            triangles: qualities.len(),
+            min_angle: distribution(|q| q.min_angle),
+            max_angle: distribution(|q| q.max_angle),
+            aspect_ratio: distribution(|q| q.aspect_ratio),
+            radius_edge_ratio: distribution(|q| q.radius_edge_ratio),
+            area: distribution(|q| q.area),
+            edge_length: Distribution::new(&edge_lengths, bins),
+            worst: order,
+        }
+    }
+}
+
+impl fmt::Display for QualityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} triangles", self.triangles)?;
+        writeln!(
+            f,
+            "{:<18} {:>12} {:>12} {:>12}",
+            "measure", "min", "mean", "max"
+        )?;
+        for (name, d) in [
+            ("min angle", &self.min_angle),
+            ("max angle", &self.max_angle),
+            ("aspect ratio", &self.aspect_ratio),
+            ("radius-edge ratio", &self.radius_edge_ratio),
+            ("area", &self.area),
+            ("edge length", &self.edge_length),
+        ] {
+            writeln!(
+                f,
+                "{name:<18} {:>12.6} {:>12.6} {:>12.6}",
+                d.min, d.mean, d.max
+            )?;
+        }
+        write!(f, "worst triangles: {:?}", self.worst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{generate, triangulate};
+
+    #[test]
+    fn test_equilateral() {
+        let q = TriangleQuality::new(
+            Vertex::new(0.0, 0.0),
+            Vertex::new(1.0, 0.0),
+            Vertex::new(0.5, 3f64.sqrt() / 2.0),
+        );
+        assert!((q.min_angle - 60.0).abs() < 1e-9);
+        assert!((q.max_angle - 60.0).abs() < 1e-9);
+        assert!((q.aspect_ratio - 1.0).abs() < 1e-9);
+        assert!((q.radius_edge_ratio - 1.0 / 3f64.sqrt()).abs() < 1e-9);
+        assert!((q.area - 3f64.sqrt() / 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_right_triangle() {
+        let q = TriangleQuality::new(
+            Vertex::new(0.0, 0.0),
+            Vertex::new(2.0, 0.0),
+            Vertex::new(0.0, 1.0),
+        );
+        assert!((q.min_angle - 0.5f64.atan().to_degrees()).abs() < 1e-9);
+        assert!((q.max_angle - 90.0).abs() < 1e-9);
+        assert!((q.area - 1.0).abs() < 1e-12);
+        assert!((q.radius_edge_ratio - 5f64.sqrt() / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let h = Histogram::new(&[0.0, 0.5, 1.0, 1.5, 2.0, 4.0], 4);
+        assert_eq!((h.min, h.max), (0.0, 4.0));
+        assert_eq!(h.counts, [2, 2, 1, 1]);
+        assert_eq!(h.bins().nth(2), Some((2.0, 1)));
+    }
+
+    #[test]
+    fn test_report() {
+        let points = generate::jittered_grid(Vertex::ZERO, Vertex::splat(1.0), 10, 10, 0.5, 3);
+        let (t, _) = triangulate(points);
+        let report = t.quality_report(8, 5);
+
+        assert_eq!(report.triangles, t.triangles().len() / 3);
+        assert_eq!(
+            report.min_angle.histogram.counts.iter().sum::<usize>(),
+            report.triangles
+        );
+        let edges = (3 * report.triangles + t.hull().len()) / 2;
+        assert_eq!(
+            report.edge_length.histogram.counts.iter().sum::<usize>(),
+            edges
+        );
+
+        // the triangles tile the convex hull
+        let hull = t.hull().iter().map(|&i| t.points()[i]).collect::<Vec<_>>();
+        let total = report.area.mean * report.triangles as f64;
+        assert!((total - polygon_area(&hull).abs()).abs() < 1e-9);
+
+        assert_eq!(report.worst.len(), 5);
+        assert_eq!(
+            t.triangle_quality(report.worst[0]).min_angle,
+            report.min_angle.min
+        );
+        assert!(report
+            .worst
+            .windows(2)
+            .all(|w| t.triangle_quality(w[0]).min_angle <= t.triangle_quality(w[1]).min_angle));
+        assert!(report.min_angle.max <= 60.0 && report.max_angle.min >= 60.0);
+        assert!(report
+            .to_string()
+            .starts_with(&format!("{} triangles", report.triangles)));
+    }
+}
@@ -0,0 +1,797 @@
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::diagram::Diagram;
+use crate::locate::walk;
+use crate::types::{EdgeIndex, VertIndex, Vertex, WeightedVertex};
+use crate::util::{convex_hull_of, in_power_circle, orient2d_fast, power_center, power_circle};
+use crate::{Dedup, Triangulation, DEFAULT_FLIP_STACK};
+
+/// Computes the regular (weighted Delaunay) triangulation of a set of weighted points.
+///
+/// Points whose weight is too small to own a cell of the power diagram are left out
+/// of the triangulation and reported by [`RegularTriangulation::hidden`].
+/// With all weights equal this is the Delaunay triangulation.
+pub fn triangulate_weighted(points: Vec<impl Into<WeightedVertex>>) -> RegularTriangulation {
+    let points = points.into_iter().map(Into::into).collect::<Vec<_>>();
+    Builder::new(points).build()
+}
+
+#[derive(Debug)]
+pub struct RegularTriangulation {
+    triangulation: Triangulation,
+    weights: Vec<f64>,
+    hidden: Vec<VertIndex>,
+}
+
+impl RegularTriangulation {
+    pub fn triangulation(&self) -> &Triangulation {
+        &self.triangulation
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// The redundant points, in ascending order. They appear in no triangle.
+    pub fn hidden(&self) -> &[VertIndex] {
+        &self.hidden
+    }
+
+    pub fn is_hidden(&self, i: VertIndex) -> bool {
+        self.hidden
+            .binary_search_by_key(&i.get(), |h| h.get())
+            .is_ok()
+    }
+
+    pub fn weighted_point(&self, i: VertIndex) -> WeightedVertex {
+        let p = self.triangulation.points[i];
+        WeightedVertex::new(p.x(), p.y(), self.weights[i])
+    }
+
+    /// The power diagram, dual to this triangulation. Hidden points have empty cells.
+    pub fn power_diagram(&self) -> Diagram {
+        let centers = self
+            .triangulation
+            .triangles
+            .chunks_exact(3)
+            .map(|t| {
+                power_center(
+                    self.weighted_point(t[0]),
+                    self.weighted_point(t[1]),
+                    self.weighted_point(t[2]),
+                )
+            })
+            .collect();
+        Diagram::new(&self.triangulation, centers)
+    }
+}
+
+enum Location {
+    Inside(usize),
+    Edge(usize),
+    Vertex(usize),
+}
+
+/// Incremental flipping (Edelsbrunner & Shah): the hull vertices are triangulated first,
+/// then every other point is located, tested for redundancy, split in and flipped
+/// with 2-2 flips, or 3-1 flips where an existing vertex becomes redundant.
+struct Builder {
+    points: Vec<WeightedVertex>,
+    triangles: Vec<VertIndex>,
+    half_edges: Vec<Option<EdgeIndex>>,
+    alive: Vec<bool>,
+    free: Vec<usize>,
+    hidden: Vec<bool>,
+    last: usize,
+}
+
+fn next(e: usize) -> usize {
+    if e % 3 == 2 {
+        e - 2
+    } else {
+        e + 1
+    }
+}
+
+fn prev(e: usize) -> usize {
+    if e.is_multiple_of(3) {
+        e + 2
+    } else {
+        e - 1
+    }
+}
+
+impl Builder {
+    fn new(points: Vec<WeightedVertex>) -> Self {
+        let n = points.len();
+        let max_triangles = (2 * n).saturating_sub(5);
+
+        Self {
+            points,
+            triangles: Vec::with_capacity(max_triangles * 3),
+            half_edges: Vec::with_capacity(max_triangles * 3),
+            alive: Vec::with_capacity(max_triangles),
+            free: Vec::new(),
+            hidden: vec![false; n],
+            last: 0,
+        }
+    }
+
+    fn build(mut self) -> RegularTriangulation {
+        let vertices = self.points.iter().map(|p| p.vertex()).collect::<Vec<_>>();
+
+        // heaviest first, so the hull keeps the heaviest of coincident points
+        let mut ids = (0..self.points.len()).collect::<Vec<_>>();
+        ids.sort_by(|&a, &b| self.points[b].w().total_cmp(&self.points[a].w()));
+        let hull = convex_hull_of(&vertices, ids, true);
+
+        let corners = (0..hull.len())
+            .filter(|&k| {
+                let a = vertices[hull[(k + hull.len() - 1) % hull.len()]];
+                let c = vertices[hull[(k + 1) % hull.len()]];
+                orient2d_fast(a, vertices[hull[k]], c) != 0.0
+            })
+            .collect::<Vec<_>>();
+
+        if corners.len() < 3 {
+            // all points are collinear: nothing to triangulate
+            return self.finish(vertices, corners.into_iter().map(|k| hull[k]).collect());
+        }
+
+        let flats = self.hide_boundary(&vertices, &hull, &corners);
+        let mut on_hull = vec![false; self.points.len()];
+        hull.iter().for_each(|&i| on_hull[i] = true);
+
+        self.seed(&corners.iter().map(|&k| hull[k]).collect::<Vec<_>>());
+        for i in flats {
+            self.insert(i);
+        }
+        for i in (0..self.points.len()).filter(|&i| !on_hull[i]) {
+            self.insert(i);
+        }
+
+        self.finish(vertices, Vec::new())
+    }
+
+    // points between two hull corners form a 1D regular triangulation of their own,
+    // so the redundant ones can be found up front; returns the remaining ones
+    fn hide_boundary(
+        &mut self,
+        vertices: &[Vertex],
+        hull: &[usize],
+        corners: &[usize],
+    ) -> Vec<usize> {
+        let mut flats = Vec::new();
+        for (k, &c0) in corners.iter().enumerate() {
+            let c1 = corners[(k + 1) % corners.len()];
+            let (a, b) = (vertices[hull[c0]], vertices[hull[c1]]);
+            let dir = b - a;
+            let lift = |i: usize| {
+                let p = vertices[i];
+                let d = p - a;
+                (
+                    d.x() * dir.x() + d.y() * dir.y(),
+                    p.length_squared() - self.points[i].w(),
+                )
+            };
+
+            let mut chain: Vec<usize> = vec![hull[c0]];
+            let mut j = (c0 + 1) % hull.len();
+            loop {
+                let i = hull[j];
+                let (s3, z3) = lift(i);
+                while chain.len() >= 2 {
+                    let (s1, z1) = lift(chain[chain.len() - 2]);
+                    let (s2, z2) = lift(chain[chain.len() - 1]);
+                    if (s2 - s1) * (z3 - z1) - (z2 - z1) * (s3 - s1) > 0.0 {
+                        break;
+                    }
+                    self.hidden[chain.pop().unwrap()] = true;
+                }
+                chain.push(i);
+                if j == c1 {
+                    break;
+                }
+                j = (j + 1) % hull.len();
+            }
+            flats.extend_from_slice(&chain[1..chain.len() - 1]);
+        }
+        flats
+    }
+
+    // fan out the strictly convex hull, then flip it into the regular triangulation;
+    // every quad of a convex polygon is convex so plain Lawson flips suffice
+    fn seed(&mut self, corners: &[usize]) {
+        for k in 1..corners.len() - 1 {
+            let t = self.add_triangle(corners[0], corners[k], corners[k + 1]);
+            if k > 1 {
+                self.link(t, Some((t - 1).into()));
+            }
+        }
+
+        let mut stack = (0..self.triangles.len()).collect::<Vec<_>>();
+        while let Some(e) = stack.pop() {
+            let Some(f) = self.half_edges[e] else {
+                continue;
+            };
+            let f = f.get();
+            let x = self.triangles[prev(e)].get();
+            let a = self.triangles[e].get();
+            let b = self.triangles[next(e)].get();
+            let d = self.triangles[prev(f)].get();
+            if self.conflicts(a, b, x, d) && self.is_convex(x, a, d, b) {
+                let (s1, s2) = self.flip(e);
+                stack.extend([s1, s1 + 2, s2, s2 + 1]);
+            }
+        }
+    }
+
+    fn insert(&mut self, i: usize) {
+        let p = self.points[i];
+        let stack = match self.locate(p.vertex()) {
+            Location::Vertex(e) => {
+                let v = self.triangles[e].get();
+                if p.w() <= self.points[v].w() {
+                    self.hidden[i] = true;
+                    return;
+                }
+
+                // the heavier point takes over the coincident vertex
+                self.hidden[v] = true;
+                self.star(e)
+                    .into_iter()
+                    .map(|e| {
+                        self.triangles[e] = i.into();
+                        next(e)
+                    })
+                    .collect()
+            }
+            Location::Inside(t) | Location::Edge(t) if self.is_redundant(t - t % 3, i) => {
+                self.hidden[i] = true;
+                return;
+            }
+            Location::Inside(t) => self.split_triangle(t, i),
+            Location::Edge(e) => self.split_edge(e, i),
+        };
+        self.restore(i, stack);
+    }
+
+    // flip the link of the freshly inserted point until it is regular again
+    fn restore(&mut self, i: usize, mut stack: Vec<usize>) {
+        // edges that could not be flipped yet, checked again once the link has changed
+        let mut waiting = Vec::new();
+        let mut changed = false;
+        loop {
+            let Some(e) = stack.pop() else {
+                if !changed || waiting.is_empty() {
+                    break;
+                }
+                stack.append(&mut waiting);
+                changed = false;
+                continue;
+            };
+            if !self.alive[e / 3] || self.triangles[prev(e)].get() != i {
+                continue;
+            }
+            self.last = e - e % 3;
+
+            let Some(f) = self.half_edges[e] else {
+                continue;
+            };
+            let f = f.get();
+            let a = self.triangles[e].get();
+            let b = self.triangles[next(e)].get();
+            let d = self.triangles[prev(f)].get();
+            if !self.conflicts(a, b, i, d) {
+                continue;
+            }
+
+            let turn_a = self.orient(i, a, d);
+            let turn_b = self.orient(d, b, i);
+            if turn_a > 0.0 && turn_b > 0.0 {
+                let (s1, s2) = self.flip(e);
+                stack.extend([s1, s2]);
+            } else {
+                let edges = self.remove_vertex(e, f, turn_a, turn_b);
+                if edges.is_empty() {
+                    waiting.push(e);
+                    continue;
+                }
+                stack.extend(edges);
+            }
+            changed = true;
+        }
+    }
+
+    // A non-convex quad (i, a, d, b) is resolved by removing its reflex corner when that
+    // is surrounded by exactly three triangles (3-1 flip), or its flat corner, lying on
+    // the segment from i to d, when that is surrounded by four (4-2 flip). Returns the new
+    // edges of the link of i, or none when neither applies yet and the edge has to wait
+    // for other flips around i.
+    fn remove_vertex(&mut self, e: usize, f: usize, turn_a: f64, turn_b: f64) -> Vec<usize> {
+        let i = self.triangles[prev(e)].get();
+        let (a, b, d) = (
+            self.triangles[e].get(),
+            self.triangles[next(e)].get(),
+            self.triangles[prev(f)].get(),
+        );
+        let twin = |s: &Self, e: usize| s.half_edges[e];
+
+        if turn_a <= 0.0 {
+            // the triangle (a, i, c) on the other side of i -> a
+            let Some(g) = twin(self, prev(e)).map(|g| g.get()) else {
+                return Vec::new();
+            };
+            let c = self.triangles[prev(g)].get();
+            let (db, bi, ic) = (
+                twin(self, prev(f)),
+                twin(self, next(e)),
+                twin(self, next(g)),
+            );
+            let s1 = e - e % 3;
+
+            if turn_a < 0.0 && c == d {
+                self.kill([f, g]);
+                self.set_linked(s1, [d, b, i], [db, bi, ic]);
+                self.hidden[a] = true;
+                return vec![s1];
+            }
+            // the triangle (d, a, c) closing the star of a
+            match twin(self, next(f)).map(|h| h.get()) {
+                Some(h) if turn_a == 0.0 && self.triangles[prev(h)].get() == c => {
+                    let cd = twin(self, prev(h));
+                    let s3 = g - g % 3;
+                    self.kill([f, h]);
+                    self.set_linked(s1, [d, b, i], [db, bi, Some((s3 + 1).into())]);
+                    self.set_linked(s3, [c, d, i], [cd, Some((s1 + 2).into()), ic]);
+                    self.hidden[a] = true;
+                    vec![s1, s3]
+                }
+                _ => Vec::new(),
+            }
+        } else {
+            // the triangle (i, b, c) on the other side of b -> i
+            let Some(g) = twin(self, next(e)).map(|g| g.get()) else {
+                return Vec::new();
+            };
+            let c = self.triangles[prev(g)].get();
+            let (ad, ci, ia) = (
+                twin(self, next(f)),
+                twin(self, prev(g)),
+                twin(self, prev(e)),
+            );
+            let s1 = e - e % 3;
+
+            if turn_b < 0.0 && c == d {
+                self.kill([f, g]);
+                self.set_linked(s1, [a, d, i], [ad, ci, ia]);
+                self.hidden[b] = true;
+                return vec![s1];
+            }
+            // the triangle (b, d, c) closing the star of b
+            match twin(self, prev(f)).map(|h| h.get()) {
+                Some(h) if turn_b == 0.0 && self.triangles[prev(h)].get() == c => {
+                    let dc = twin(self, next(h));
+                    let s3 = g - g % 3;
+                    self.kill([f, h]);
+                    self.set_linked(s1, [a, d, i], [ad, Some((s3 + 2).into()), ia]);
+                    self.set_linked(s3, [d, c, i], [dc, ci, Some((s1 + 1).into())]);
+                    self.hidden[b] = true;
+                    vec![s1, s3]
+                }
+                _ => Vec::new(),
+            }
+        }
+    }
+
+    // the same walk as `Triangulation::locate`, from the last triangle touched
+    fn locate(&self, p: Vertex) -> Location {
+        let vertex = |v: VertIndex| self.vertex(v.get());
+        match walk(&self.triangles, &self.half_edges, vertex, p, self.last / 3) {
+            (crate::Location::Triangle(_), e) => Location::Inside(e.get()),
+            (crate::Location::Vertex(_), e) => Location::Vertex(e.get()),
+            (crate::Location::Edge(_) | crate::Location::Outside(_), e) => Location::Edge(e.get()),
+        }
+    }
+
+    // all edges leaving the vertex that `e` starts at
+    fn star(&self, e: usize) -> Vec<usize> {
+        let mut edges = vec![e];
+        let mut out = e;
+        while let Some(g) = self.half_edges[prev(out)] {
+            out = g.get();
+            if out == e {
+                return edges;
+            }
+            edges.push(out);
+        }
+        let mut out = e;
+        while let Some(g) = self.half_edges[out] {
+            out = next(g.get());
+            edges.push(out);
+        }
+        edges
+    }
+
+    fn split_triangle(&mut self, t: usize, i: usize) -> Vec<usize> {
+        let (a, b, c) = (
+            self.triangles[t].get(),
+            self.triangles[t + 1].get(),
+            self.triangles[t + 2].get(),
+        );
+        let (ab, bc, ca) = (
+            self.half_edges[t],
+            self.half_edges[t + 1],
+            self.half_edges[t + 2],
+        );
+
+        self.set_triangle(t, a, b, i);
+        let t1 = self.add_triangle(b, c, i);
+        let t2 = self.add_triangle(c, a, i);
+
+        self.link(t, ab);
+        self.link(t1, bc);
+        self.link(t2, ca);
+        self.link(t + 1, Some((t1 + 2).into()));
+        self.link(t1 + 1, Some((t2 + 2).into()));
+        self.link(t2 + 1, Some((t + 2).into()));
+
+        vec![t, t1, t2]
+    }
+
+    fn split_edge(&mut self, e: usize, i: usize) -> Vec<usize> {
+        let s1 = e - e % 3;
+        let (u, v, w1) = (
+            self.triangles[e].get(),
+            self.triangles[next(e)].get(),
+            self.triangles[prev(e)].get(),
+        );
+        let (vw, wu) = (self.half_edges[next(e)], self.half_edges[prev(e)]);
+        let other = self.half_edges[e].map(|f| {
+            let f = f.get();
+            (
+                f,
+                self.triangles[prev(f)].get(),
+                self.half_edges[next(f)],
+                self.half_edges[prev(f)],
+            )
+        });
+
+        self.set_triangle(s1, w1, u, i);
+        let sb = self.add_triangle(v, w1, i);
+        self.link(s1, wu);
+        self.link(sb, vw);
+        self.link(sb + 1, Some((s1 + 2).into()));
+
+        let Some((f, w2, uw, wv)) = other else {
+            self.link(s1 + 1, None);
+            self.link(sb + 2, None);
+            return vec![s1, sb];
+        };
+
+        let s2 = f - f % 3;
+        self.set_triangle(s2, w2, v, i);
+        let sd = self.add_triangle(u, w2, i);
+        self.link(s2, wv);
+        self.link(sd, uw);
+        self.link(s2 + 1, Some((sb + 2).into()));
+        self.link(sd + 1, Some((s2 + 2).into()));
+        self.link(sd + 2, Some((s1 + 1).into()));
+
+        vec![s1, sb, s2, sd]
+    }
+
+    // replaces the triangles (a, b, x) and (b, a, d) sharing edge `e` with (a, d, x) and (d, b, x)
+    fn flip(&mut self, e: usize) -> (usize, usize) {
+        let f = self.half_edges[e].unwrap().get();
+        let (s1, s2) = (e - e % 3, f - f % 3);
+        let (a, b, x, d) = (
+            self.triangles[e].get(),
+            self.triangles[next(e)].get(),
+            self.triangles[prev(e)].get(),
+            self.triangles[prev(f)].get(),
+        );
+        let (ad, db, bx, xa) = (
+            self.half_edges[next(f)],
+            self.half_edges[prev(f)],
+            self.half_edges[next(e)],
+            self.half_edges[prev(e)],
+        );
+
+        self.set_triangle(s1, a, d, x);
+        self.set_triangle(s2, d, b, x);
+        self.link(s1, ad);
+        self.link(s1 + 1, Some((s2 + 2).into()));
+        self.link(s1 + 2, xa);
+        self.link(s2, db);
+        self.link(s2 + 1, bx);
+
+        (s1, s2)
+    }
+
+    fn kill(&mut self, edges: [usize; 2]) {
+        for e in edges {
+            self.alive[e / 3] = false;
+            self.free.push(e - e % 3);
+        }
+    }
+
+    fn set_linked(&mut self, t: usize, [a, b, c]: [usize; 3], twins: [Option<EdgeIndex>; 3]) {
+        self.set_triangle(t, a, b, c);
+        for (k, twin) in twins.into_iter().enumerate() {
+            self.link(t + k, twin);
+        }
+    }
+
+    fn add_triangle(&mut self, a: usize, b: usize, c: usize) -> usize {
+        let t = match self.free.pop() {
+            Some(t) => t,
+            None => {
+                self.triangles.extend([VertIndex::default(); 3]);
+                self.half_edges.extend([None; 3]);
+                self.alive.push(false);
+                self.triangles.len() - 3
+            }
+        };
+        self.alive[t / 3] = true;
+        self.set_triangle(t, a, b, c);
+        self.half_edges[t..t + 3].fill(None);
+        t
+    }
+
+    fn set_triangle(&mut self, t: usize, a: usize, b: usize, c: usize) {
+        self.triangles[t] = a.into();
+        self.triangles[t + 1] = b.into();
+        self.triangles[t + 2] = c.into();
+    }
+
+    fn link(&mut self, a: usize, b: Option<EdgeIndex>) {
+        self.half_edges[a] = b;
+        if let Some(b) = b {
+            self.half_edges[b] = Some(a.into());
+        }
+    }
+
+    fn vertex(&self, i: usize) -> Vertex {
+        self.points[i].vertex()
+    }
+
+    fn orient(&self, a: usize, b: usize, c: usize) -> f64 {
+        orient2d_fast(self.vertex(a), self.vertex(b), self.vertex(c))
+    }
+
+    // whether d lies below the plane through the lifted triangle (a, b, c)
+    fn conflicts(&self, a: usize, b: usize, c: usize, d: usize) -> bool {
+        in_power_circle(
+            self.points[a],
+            self.points[b],
+            self.points[c],
+            self.points[d],
+        )
+    }
+
+    fn is_convex(&self, p: usize, a: usize, d: usize, b: usize) -> bool {
+        self.orient(p, a, d) > 0.0 && self.orient(d, b, p) > 0.0
+    }
+
+    fn is_redundant(&self, t: usize, i: usize) -> bool {
+        power_circle(
+            self.points[self.triangles[t].get()],
+            self.points[self.triangles[t + 1].get()],
+            self.points[self.triangles[t + 2].get()],
+            self.points[i],
+        ) >= 0.0
+    }
+
+    fn finish(self, vertices: Vec<Vertex>, degenerate_hull: Vec<usize>) -> RegularTriangulation {
+        // compact the surviving triangles
+        let mut remap = vec![usize::MAX; self.alive.len()];
+        let mut triangles = Vec::with_capacity(self.triangles.len());
+        for (t, _) in self.alive.iter().enumerate().filter(|(_, alive)| **alive) {
+            remap[t] = triangles.len() / 3;
+            triangles.extend_from_slice(&self.triangles[t * 3..t * 3 + 3]);
+        }
+        let half_edges = self
+            .alive
+            .iter()
+            .enumerate()
+            .filter(|(_, alive)| **alive)
+            .flat_map(|(t, _)| &self.half_edges[t * 3..t * 3 + 3])
+            .map(|h| h.map(|h| (remap[h.get() / 3] * 3 + h.get() % 3).into()))
+            .collect::<Vec<Option<EdgeIndex>>>();
+
+        let mut hull: Vec<VertIndex> = degenerate_hull.into_iter().map(Into::into).collect();
+        if let Some(start) = half_edges.iter().position(Option::is_none) {
+            let mut e = start;
+            loop {
+                hull.push(triangles[e]);
+                e = next(e);
+                while let Some(t) = half_edges[e] {
+                    e = next(t.get());
+                }
+                if e == start {
+                    break;
+                }
+            }
+        }
+
+        let weights = self.points.iter().map(|p| p.w()).collect();
+        let hidden = (0..self.hidden.len())
+            .filter(|&i| self.hidden[i])
+            .map(Into::into)
+            .collect();
+
+        RegularTriangulation {
+            triangulation: Triangulation {
+                duplicate_of: vec![None; vertices.len()],
+                points: vertices,
+                triangles,
+                half_edges,
+                hull,
+                dedup: Dedup::default(),
+                robust: false,
+                flip_stack: DEFAULT_FLIP_STACK,
+                constraints: BTreeSet::new(),
+                vertex_index: None,
+                hull_moved: false,
+            },
+            weights,
+            hidden,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    // no lifted point may lie below the plane of any lifted triangle
+    fn assert_regular(r: &RegularTriangulation) {
+        let t = r.triangulation();
+        for tri in t.triangles().chunks_exact(3) {
+            let (a, b, c) = (
+                r.weighted_point(tri[0]),
+                r.weighted_point(tri[1]),
+                r.weighted_point(tri[2]),
+            );
+            assert!(orient2d_fast(a.vertex(), b.vertex(), c.vertex()) > 0.0);
+            for i in 0..t.points().len() {
+                if tri.contains(&i.into()) {
+                    continue;
+                }
+                let p = r.weighted_point(i.into());
+                assert!(
+                    power_circle(a, b, c, p) >= -1e-9,
+                    "{i} conflicts with {tri:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unweighted_is_delaunay() {
+        let r = triangulate_weighted(generate::uniform(200, Vertex::ZERO, Vertex::splat(1.0), 2));
+        let t = r.triangulation();
+
+        // without weights the lifted test is the plain incircle test
+        assert!(r.hidden().is_empty());
+        assert_regular(&r);
+        assert_eq!(t.triangles().len() / 3, 2 * 200 - 2 - t.hull().len());
+    }
+
+    #[test]
+    fn test_hidden_points() {
+        let points = vec![
+            WeightedVertex::new(0.0, 0.0, 0.0),
+            WeightedVertex::new(1.0, 0.0, 0.0),
+            WeightedVertex::new(0.0, 1.0, 0.0),
+            WeightedVertex::new(1.0, 1.0, 0.0),
+            // a heavy point swallowing its light neighbour
+            WeightedVertex::new(0.5, 0.5, 0.2),
+            WeightedVertex::new(0.55, 0.5, 0.0),
+            // a lighter duplicate of a hull corner
+            WeightedVertex::new(1.0, 1.0, -0.1),
+            // a light point on the hull between two corners
+            WeightedVertex::new(0.5, 0.0, -0.5),
+        ];
+        let r = triangulate_weighted(points);
+
+        assert_eq!(r.hidden(), [5.into(), 6.into(), 7.into()]);
+        assert_eq!(r.triangulation().triangles().len(), 4 * 3);
+        assert_eq!(r.triangulation().hull().len(), 4);
+        assert_regular(&r);
+    }
+
+    #[test]
+    fn test_random_weights() {
+        let points = generate::uniform(600, Vertex::ZERO, Vertex::splat(1.0), 3);
+        let r = triangulate_weighted(
+            points[..300]
+                .iter()
+                .zip(&points[300..])
+                .map(|(p, w)| WeightedVertex::new(p.x(), p.y(), w.x() * 0.01))
+                .collect(),
+        );
+
+        assert!(!r.hidden().is_empty());
+        assert_regular(&r);
+
+        // every visible point is used by some triangle
+        let used = r.triangulation().triangles().iter().map(|t| t.get());
+        let mut seen = vec![false; 300];
+        used.for_each(|i| seen[i] = true);
+        for (i, seen) in seen.into_iter().enumerate() {
+            assert_eq!(seen, !r.is_hidden(i.into()));
+        }
+    }
+
+    #[test]
+    fn test_heavy_weights() {
+        // heavy enough that many inserts meet reflex corners that cannot be removed yet
+        let points = generate::uniform(400, Vertex::ZERO, Vertex::splat(1.0), 5);
+        let weights = generate::uniform(400, Vertex::ZERO, Vertex::splat(0.01), 6);
+        let r = triangulate_weighted(
+            points
+                .iter()
+                .zip(&weights)
+                .map(|(p, w)| WeightedVertex::new(p.x(), p.y(), w.x()))
+                .collect(),
+        );
+
+        assert!(r.hidden().len() > 100);
+        assert_regular(&r);
+        let t = r.triangulation();
+        let visible = 400 - r.hidden().len();
+        assert_eq!(t.triangles().len() / 3, 2 * visible - 2 - t.hull().len());
+    }
+
+    #[test]
+    fn test_grid_weights() {
+        // cocircular and collinear everywhere, with duplicates
+        let weights = generate::uniform(100, Vertex::ZERO, Vertex::splat(1.0), 4);
+        let r = triangulate_weighted(
+            (0..100)
+                .map(|i| {
+                    let (x, y) = ((i % 7) as f64, (i / 7 % 7) as f64);
+                    WeightedVertex::new(x, y, weights[i].x())
+                })
+                .collect(),
+        );
+
+        assert!(!r.hidden().is_empty());
+        assert_regular(&r);
+    }
+
+    #[test]
+    fn test_power_diagram() {
+        let r = triangulate_weighted(vec![
+            [0.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [2.0, 2.0, 0.0],
+            [1.0, 1.2, 0.3],
+        ]);
+        let diagram = r.power_diagram();
+
+        assert_eq!(diagram.vertices().len(), 4);
+        assert!(diagram.cell(4.into()).is_bounded());
+        assert_eq!(diagram.cell(4.into()).vertices().len(), 4);
+        for i in 0..4 {
+            let cell = diagram.cell(i.into());
+            assert!(!cell.is_bounded());
+            assert_eq!(cell.vertices().len(), 2);
+        }
+
+        // the power centers are equally far, in power distance, from their three sites
+        let triangles = r.triangulation().triangles();
+        for (t, c) in diagram.vertices().iter().enumerate() {
+            let d = (0..3)
+                .map(|k| r.weighted_point(triangles[t * 3 + k]).power_distance(*c))
+                .collect::<Vec<_>>();
+            assert!((d[0] - d[1]).abs() < 1e-9 && (d[1] - d[2]).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::{PI, TAU};
+
+use crate::math;
+use crate::predicates::{in_circle_exact, orient2d};
+use crate::types::{EdgeIndex, VertIndex, Vertex};
+use crate::util::{circumcenter, orient2d_fast, polygon_area};
+use crate::Triangulation;
+
+/// How [`Smoother`] picks the new position of a vertex from its star.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SmoothingMethod {
+    /// The average of the neighbors.
+    #[default]
+    Laplacian,
+    /// The average of the neighbors' angle bisectors, at the vertex's current distance
+    /// from each neighbor (Zhou and Shimada). Keeps angles more even than Laplacian.
+    Angle,
+    /// The area-weighted average of the circumcenters of the incident triangles,
+    /// which minimizes the interpolation error of the star (Chen and Xu).
+    Odt,
+}
+
+/// Mesh smoothing: moves vertices to improve triangle shapes without changing
+/// connectivity, then flips edges to make the triangulation Delaunay again.
+///
+/// Hull vertices never move, so the triangulated region stays the same.
+#[derive(Debug, Clone, Default)]
+pub struct Smoother {
+    method: SmoothingMethod,
+    iterations: usize,
+    locked: Vec<VertIndex>,
+}
+
+/// The outcome of [`Smoother::smooth`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Smoothing {
+    /// The number of passes performed.
+    pub iterations: usize,
+    /// The largest distance a vertex moved in the last pass.
+    pub displacement: f64,
+}
+
+impl Smoother {
+    pub fn new(method: SmoothingMethod) -> Self {
+        Self {
+            method,
+            iterations: 1,
+            locked: Vec::new(),
+        }
+    }
+
+    /// The number of smoothing passes, each followed by re-Delaunayization.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Keeps these vertices in place, in addition to the hull.
+    pub fn lock(mut self, vertices: impl IntoIterator<Item = VertIndex>) -> Self {
+        self.locked.extend(vertices);
+        self
+    }
+
+    /// Smooths an up-to-date triangulation in place.
+    pub fn smooth(&self, triangulation: &mut Triangulation) -> Smoothing {
+        let mut locked = vec![false; triangulation.points.len()];
+        for &v in self.locked.iter().chain(&triangulation.hull) {
+            locked[v] = true;
+        }
+
+        let mut smoothing = Smoothing {
+            iterations: 0,
+            displacement: 0.0,
+        };
+        for _ in 0..self.iterations {
+            let mut displacement = 0.0_f64;
+            let mut moved = Vec::new();
+            for (v, start) in triangulation.inedges().into_iter().enumerate() {
+                let Some(start) = start.filter(|_| !locked[v]) else {
+                    continue;
+                };
+                let v = VertIndex::from(v);
+                let star = triangulation.edges_around_point(start).collect::<Vec<_>>();
+                let Some(target) = self.target(triangulation, v, &star) else {
+                    continue;
+                };
+
+                // back off towards the old position until no incident triangle inverts
+                let old = triangulation.points[v];
+                let mut step = target - old;
+                for _ in 0..4 {
+                    let p = old + step;
+                    if star.iter().all(|&e| triangulation.keeps_orientation(e, p)) {
+                        triangulation.points[v] = p;
+                        moved.push(v);
+                        displacement = displacement.max(step.length());
+                        break;
+                    }
+                    step = step / 2.0;
+                }
+            }
+
+            triangulation.restore_delaunay(&moved);
+            smoothing.iterations += 1;
+            smoothing.displacement = displacement;
+        }
+        smoothing
+    }
+
+    // `star` holds the edges coming into the interior vertex `v`, in order around it
+    fn target(&self, t: &Triangulation, v: VertIndex, star: &[EdgeIndex]) -> Option<Vertex> {
+        let p = t.points[v];
+        let neighbors = star
+            .iter()
+            .map(|&e| t.points[t.triangles[e]])
+            .collect::<Vec<_>>();
+        let n = neighbors.len();
+        if n < 3 {
+            return None;
+        }
+
+        match self.method {
+            SmoothingMethod::Laplacian => {
+                Some(neighbors.iter().fold(Vertex::ZERO, |s, &q| s + q) / n as f64)
+            }
+            SmoothingMethod::Angle => {
+                let angle = |u: Vertex| math::atan2(u.y(), u.x());
+                let sum = (0..n).fold(Vertex::ZERO, |sum, j| {
+                    // turn v about the neighbor q onto the bisector of the angle
+                    // between the neighbors next to q, which contains v
+                    let q = neighbors[j];
+                    let (a, b, d) = (
+                        neighbors[(j + n - 1) % n] - q,
+                        neighbors[(j + 1) % n] - q,
+                        p - q,
+                    );
+                    let wrap = |x: f64| math::rem_euclid(x + PI, TAU) - PI;
+                    let sweep = wrap(angle(d) - angle(a)) + wrap(angle(b) - angle(d));
+                    let theta = angle(a) + sweep / 2.0;
+                    sum + q + Vertex::new(math::cos(theta), math::sin(theta)) * d.length()
+                });
+                Some(sum / n as f64)
+            }
+            SmoothingMethod::Odt => {
+                let (mut mass, mut moment) = (0.0, Vertex::ZERO);
+                for &e in star {
+                    let t0 = e.get() / 3 * 3;
+                    let [a, b, c] = [0, 1, 2].map(|i| t.points[t.triangles[t0 + i]]);
+                    let area = polygon_area(&[a, b, c]).abs();
+                    mass += area;
+                    moment = moment + circumcenter(a, b, c) * area;
+                }
+                (mass > 0.0).then(|| moment / mass)
+            }
+        }
+    }
+}
+
+impl Triangulation {
+    /// Flips edges until every edge is locally Delaunay again, after the points `moved`
+    /// have moved without inverting any triangle. The hull vertices must not have moved.
+    ///
+    /// Only the edges of triangles around the moved points are checked at first, then the
+    /// sides of every flipped quad. The tests are exact, so the flipping always ends.
+    pub fn restore_delaunay(&mut self, moved: &[VertIndex]) {
+        let mut around = vec![false; self.points.len()];
+        for &v in moved {
+            around[v] = true;
+        }
+        let mut stack = Vec::new();
+        for (t, corners) in self.triangles.chunks_exact(3).enumerate() {
+            if corners.iter().any(|&v| around[v]) {
+                stack.extend((3 * t..3 * t + 3).map(EdgeIndex::from));
+            }
+        }
+
+        while let Some(e) = stack.pop() {
+            let Some(twin) = self.half_edges[e] else {
+                continue;
+            };
+            if self.is_constrained(e) {
+                continue;
+            }
+            let [p0, pr, pl, p1] = [
+                Self::prev_half_edge(e),
+                e,
+                Self::next_half_edge(e),
+                Self::prev_half_edge(twin),
+            ]
+            .map(|e| self.points[self.triangles[e]]);
+            if !in_circle_exact(p0, pr, pl, p1)
+                || orient2d(p1, pl, p0) <= 0.0
+                || orient2d(p0, pr, p1) <= 0.0
+            {
+                continue;
+            }
+
+            self.swap_diagonal(e.get(), twin);
+            for slot in [e, twin] {
+                // a hull edge moved into the slot; the hull context catches up on its next use
+                self.hull_moved |= self.half_edges[slot].is_none();
+                stack.extend([slot, Self::next_half_edge(slot), Self::prev_half_edge(slot)]);
+            }
+        }
+    }
+
+    // whether the triangle of the edge coming into a vertex stays positively
+    // oriented when that vertex moves to `p`
+    fn keeps_orientation(&self, incoming: EdgeIndex, p: Vertex) -> bool {
+        let a = self.points[self.triangles[Self::prev_half_edge(incoming)]];
+        let b = self.points[self.triangles[incoming]];
+        orient2d_fast(a, b, p) > 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate, HullContext};
+
+    fn assert_delaunay(t: &Triangulation) {
+        for e in 0..t.triangles().len() {
+            assert!(t.is_locally_delaunay(e.into()), "edge {e} is not Delaunay");
+        }
+        for tri in t.triangles().chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| t.points()[tri[i]]);
+            assert!(orient2d_fast(a, b, c) > 0.0);
+        }
+    }
+
+    fn mesh() -> (Triangulation, HullContext) {
+        let mut points =
+            generate::jittered_grid(Vertex::splat(0.1), Vertex::splat(0.9), 8, 8, 0.9, 5);
+        // an evenly spaced boundary around the unit square
+        for k in 0..10 {
+            let s = k as f64 / 10.0;
+            points.extend([(s, 0.0), (1.0, s), (1.0 - s, 1.0), (0.0, 1.0 - s)].map(Vertex::from));
+        }
+        triangulate(points)
+    }
+
+    #[test]
+    fn test_methods_improve_quality() {
+        for method in [
+            SmoothingMethod::Laplacian,
+            SmoothingMethod::Angle,
+            SmoothingMethod::Odt,
+        ] {
+            let (mut t, _) = mesh();
+            let before = t.quality_report(1, 0);
+            let hull_before = t.hull().iter().map(|&i| t.points()[i]).collect::<Vec<_>>();
+
+            let s = Smoother::new(method).iterations(5).smooth(&mut t);
+            assert_eq!(s.iterations, 5);
+            assert_delaunay(&t);
+
+            let after = t.quality_report(1, 0);
+            assert_eq!(after.triangles, before.triangles);
+            assert!(
+                after.min_angle.mean > before.min_angle.mean + 5.0,
+                "{method:?}: {} -> {}",
+                before.min_angle.mean,
+                after.min_angle.mean
+            );
+            assert!(
+                after.min_angle.min > before.min_angle.min,
+                "{method:?}: {} -> {}",
+                before.min_angle.min,
+                after.min_angle.min
+            );
+
+            let hull_after = t.hull().iter().map(|&i| t.points()[i]).collect::<Vec<_>>();
+            assert_eq!(hull_after, hull_before);
+        }
+    }
+
+    #[test]
+    fn test_locked_vertices() {
+        let (mut t, _) = mesh();
+        let before = t.points().to_vec();
+        let locked = [0, 13, 27, 50].map(VertIndex::from);
+
+        Smoother::new(SmoothingMethod::Odt)
+            .iterations(3)
+            .lock(locked)
+            .smooth(&mut t);
+        for v in locked {
+            assert_eq!(t.points()[v], before[v]);
+        }
+        assert_ne!(t.points(), &before[..]);
+    }
+
+    #[test]
+    fn test_restore_delaunay() {
+        let (mut t, _) = mesh();
+        // squash the interior towards the middle row without inverting anything
+        let mut moved = Vec::new();
+        for (v, start) in t.inedges().into_iter().enumerate() {
+            let v = VertIndex::from(v);
+            if start.is_some() && !t.hull().contains(&v) {
+                let p = t.points[v];
+                t.points[v] = Vertex::new(p.x(), 0.5 + (p.y() - 0.5) * 0.9);
+                moved.push(v);
+            }
+        }
+        assert!((0..t.triangles().len()).any(|e| !t.is_locally_delaunay(e.into())));
+        t.restore_delaunay(&moved);
+        assert_delaunay(&t);
+    }
+
+    #[test]
+    fn test_insert_after_smoothing() {
+        let (mut t, mut hull) = mesh();
+        Smoother::new(SmoothingMethod::Odt)
+            .iterations(3)
+            .smooth(&mut t);
+        // joining a point outside follows the hull edges, which flips may have moved
+        for p in [(-0.2, 0.25), (0.45, 1.2), (1.3, 0.5)] {
+            t.insert(&mut hull, p);
+        }
+        assert_delaunay(&t);
+        for (e, twin) in t.half_edges().iter().enumerate() {
+            if let Some(twin) = *twin {
+                assert_eq!(t.half_edges()[twin], Some(e.into()));
+            }
+        }
+        assert_eq!(
+            t.triangles().len() / 3,
+            2 * t.points().len() - t.hull().len() - 2
+        );
+    }
+}
@@ -0,0 +1,396 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::types::{VertIndex, Vertex};
+use crate::util::circumcenter;
+use crate::Triangulation;
+
+/// Maps triangulation coordinates to an image of `width` by `height` pixels, with y up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub width: f64,
+    pub height: f64,
+    /// Pixels per unit.
+    pub scale: f64,
+    /// The point at the bottom left corner of the image.
+    pub origin: Vertex,
+}
+
+impl Viewport {
+    /// The viewport showing the box from `min` to `max` as large as it fits, centered,
+    /// with `margin` pixels to spare on every side.
+    pub fn fit(min: Vertex, max: Vertex, width: f64, height: f64, margin: f64) -> Self {
+        let size = max - min;
+        let scale = f64::min(
+            (width - 2.0 * margin) / size.x(),
+            (height - 2.0 * margin) / size.y(),
+        );
+        // a single point or a line has no extent in one direction or both
+        let scale = if scale.is_finite() && scale > 0.0 {
+            scale
+        } else {
+            1.0
+        };
+        let center = (min + max) / 2.0;
+        Self {
+            width,
+            height,
+            scale,
+            origin: center - Vertex::new(width, height) / (2.0 * scale),
+        }
+    }
+
+    /// The pixel position of `p`, measured from the top left corner.
+    pub fn apply(&self, p: Vertex) -> Vertex {
+        let q = (p - self.origin) * self.scale;
+        Vertex::new(q.x(), self.height - q.y())
+    }
+
+    // the image as a rectangle in triangulation coordinates
+    fn bounds(&self) -> [Vertex; 4] {
+        let (w, h) = (self.width / self.scale, self.height / self.scale);
+        let o = self.origin;
+        [
+            o,
+            o + Vertex::new(w, 0.0),
+            o + Vertex::new(w, h),
+            o + Vertex::new(0.0, h),
+        ]
+    }
+}
+
+/// How a line is drawn: a CSS color and a width in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke {
+    pub color: String,
+    pub width: f64,
+}
+
+impl Stroke {
+    pub fn new(color: impl Into<String>, width: f64) -> Self {
+        Self {
+            color: color.into(),
+            width,
+        }
+    }
+}
+
+// how triangles are filled
+enum Fill<'a> {
+    None,
+    Color(String),
+    Each(Box<dyn Fn(usize) -> Option<String> + 'a>),
+}
+
+/// Renders a triangulation as an SVG image.
+///
+/// Layers are drawn from the bottom up: filled triangles, Voronoi cells, circumcircles,
+/// edges, the hull and the vertices. Only edges are drawn by default.
+pub struct Svg<'a> {
+    viewport: Option<Viewport>,
+    fill: Fill<'a>,
+    edges: Option<Stroke>,
+    hull: Option<Stroke>,
+    vertices: Option<(f64, String)>,
+    circumcircles: Option<Stroke>,
+    voronoi: Option<Stroke>,
+}
+
+impl fmt::Debug for Svg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fill = match &self.fill {
+            Fill::None => None,
+            Fill::Color(color) => Some(color.as_str()),
+            Fill::Each(_) => Some("<callback>"),
+        };
+        f.debug_struct("Svg")
+            .field("viewport", &self.viewport)
+            .field("fill", &fill)
+            .field("edges", &self.edges)
+            .field("hull", &self.hull)
+            .field("vertices", &self.vertices)
+            .field("circumcircles", &self.circumcircles)
+            .field("voronoi", &self.voronoi)
+            .finish()
+    }
+}
+
+impl Default for Svg<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Svg<'a> {
+    pub fn new() -> Self {
+        Self {
+            viewport: None,
+            fill: Fill::None,
+            edges: Some(Stroke::new("black", 1.0)),
+            hull: None,
+            vertices: None,
+            circumcircles: None,
+            voronoi: None,
+        }
+    }
+
+    /// Where the triangulation goes in the image. By default its points are fitted into
+    /// 800 by 800 pixels with a margin of 10.
+    pub fn viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// Fills every triangle with `color`.
+    pub fn fill(mut self, color: impl Into<String>) -> Self {
+        self.fill = Fill::Color(color.into());
+        self
+    }
+
+    /// Fills each triangle with the color returned for its index, or leaves it empty on `None`.
+    pub fn fill_with(mut self, color: impl Fn(usize) -> Option<String> + 'a) -> Self {
+        self.fill = Fill::Each(Box::new(color));
+        self
+    }
+
+    /// Draws every edge once, or no edges on `None`.
+    pub fn edges(mut self, stroke: Option<Stroke>) -> Self {
+        self.edges = stroke;
+        self
+    }
+
+    /// Outlines the convex hull.
+    pub fn hull(mut self, stroke: Stroke) -> Self {
+        self.hull = Some(stroke);
+        self
+    }
+
+    /// Marks the vertices with dots of `radius` pixels.
+    pub fn vertices(mut self, radius: f64, color: impl Into<String>) -> Self {
+        self.vertices = Some((radius, color.into()));
+        self
+    }
+
+    /// Draws the circumcircle of every triangle.
+    pub fn circumcircles(mut self, stroke: Stroke) -> Self {
+        self.circumcircles = Some(stroke);
+        self
+    }
+
+    /// Outlines the Voronoi cells, unbounded ones cut off at the edge of the image.
+    pub fn voronoi(mut self, stroke: Stroke) -> Self {
+        self.voronoi = Some(stroke);
+        self
+    }
+
+    /// The SVG document.
+    pub fn render(&self, t: &Triangulation) -> String {
+        let mut svg = String::new();
+        // writing to a string can't fail
+        let _ = self.write(&mut svg, t);
+        svg
+    }
+
+    fn write(&self, svg: &mut String, t: &Triangulation) -> fmt::Result {
+        let viewport = self.viewport.unwrap_or_else(|| {
+            let mut points = t.points.iter().copied();
+            let first = points.next().unwrap_or(Vertex::ZERO);
+            let (min, max) = points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+            Viewport::fit(min, max, 800.0, 800.0, 10.0)
+        });
+        let at = |p: Vertex| {
+            let p = viewport.apply(p);
+            format!("{},{}", number(p.x()), number(p.y()))
+        };
+        let polygon = |corners: &mut dyn Iterator<Item = Vertex>| {
+            corners.map(at).collect::<Vec<_>>().join(" ")
+        };
+        let corners = |k: usize| t.triangles[3 * k..3 * k + 3].iter().map(|&v| t.points[v]);
+        let triangle_count = t.triangles.len() / 3;
+
+        let (width, height) = (number(viewport.width), number(viewport.height));
+        write!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" "#)?;
+        writeln!(
+            svg,
+            r#"width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )?;
+
+        match &self.fill {
+            Fill::None => {}
+            Fill::Color(color) => {
+                writeln!(svg, r#"<g fill="{}" stroke="none">"#, escape(color))?;
+                for k in 0..triangle_count {
+                    writeln!(svg, r#"<polygon points="{}"/>"#, polygon(&mut corners(k)))?;
+                }
+                writeln!(svg, "</g>")?;
+            }
+            Fill::Each(color) => {
+                writeln!(svg, r#"<g stroke="none">"#)?;
+                for k in 0..triangle_count {
+                    if let Some(color) = color(k) {
+                        let points = polygon(&mut corners(k));
+                        let color = escape(&color);
+                        writeln!(svg, r#"<polygon points="{points}" fill="{color}"/>"#)?;
+                    }
+                }
+                writeln!(svg, "</g>")?;
+            }
+        }
+
+        if let Some(stroke) = &self.voronoi {
+            let diagram = t.voronoi();
+            let bounds = viewport.bounds();
+            open_group(svg, stroke)?;
+            for site in (0..t.points.len()).map(VertIndex::from) {
+                let cell = diagram.clip(site, &bounds);
+                if !cell.is_empty() {
+                    let points = polygon(&mut cell.into_iter());
+                    writeln!(svg, r#"<polygon points="{points}"/>"#)?;
+                }
+            }
+            writeln!(svg, "</g>")?;
+        }
+
+        if let Some(stroke) = &self.circumcircles {
+            open_group(svg, stroke)?;
+            for k in 0..triangle_count {
+                let [a, b, c] = [0, 1, 2].map(|i| t.points[t.triangles[3 * k + i]]);
+                let center = circumcenter(a, b, c);
+                let (p, r) = (
+                    viewport.apply(center),
+                    (a - center).length() * viewport.scale,
+                );
+                let (x, y, r) = (number(p.x()), number(p.y()), number(r));
+                writeln!(svg, r#"<circle cx="{x}" cy="{y}" r="{r}"/>"#)?;
+            }
+            writeln!(svg, "</g>")?;
+        }
+
+        if let Some(stroke) = &self.edges {
+            if !t.triangles.is_empty() {
+                open_group(svg, stroke)?;
+                let path = t
+                    .edges()
+                    .map(|(a, b)| format!("M{}L{}", at(a), at(b)))
+                    .collect::<String>();
+                writeln!(svg, r#"<path d="{path}"/>"#)?;
+                writeln!(svg, "</g>")?;
+            }
+        }
+
+        if let Some(stroke) = &self.hull {
+            if !t.hull.is_empty() {
+                open_group(svg, stroke)?;
+                let points = polygon(&mut t.hull.iter().map(|&v| t.points[v]));
+                writeln!(svg, r#"<polygon points="{points}"/>"#)?;
+                writeln!(svg, "</g>")?;
+            }
+        }
+
+        if let Some((radius, color)) = &self.vertices {
+            writeln!(svg, r#"<g fill="{}" stroke="none">"#, escape(color))?;
+            for &p in &t.points {
+                let p = viewport.apply(p);
+                let (x, y, r) = (number(p.x()), number(p.y()), number(*radius));
+                writeln!(svg, r#"<circle cx="{x}" cy="{y}" r="{r}"/>"#)?;
+            }
+            writeln!(svg, "</g>")?;
+        }
+
+        writeln!(svg, "</svg>")
+    }
+}
+
+fn open_group(svg: &mut String, stroke: &Stroke) -> fmt::Result {
+    writeln!(
+        svg,
+        r#"<g fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round">"#,
+        escape(&stroke.color),
+        number(stroke.width)
+    )
+}
+
+// pixels to two decimals, without trailing zeros
+fn number(x: f64) -> String {
+    let s = format!("{x:.2}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    match s {
+        "-0" => "0".to_string(),
+        s => s.to_string(),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::triangulate;
+
+    #[test]
+    fn test_viewport() {
+        let viewport = Viewport::fit(Vertex::ZERO, Vertex::new(2.0, 1.0), 100.0, 100.0, 10.0);
+        assert_eq!(viewport.scale, 40.0);
+        // y goes down in the image
+        assert_eq!(viewport.apply(Vertex::ZERO), Vertex::new(10.0, 70.0));
+        assert_eq!(
+            viewport.apply(Vertex::new(2.0, 1.0)),
+            Vertex::new(90.0, 30.0)
+        );
+        assert_eq!(number(1.0 / 3.0), "0.33");
+        assert_eq!(number(-0.001), "0");
+        assert_eq!(number(20.0), "20");
+    }
+
+    #[test]
+    fn test_render() {
+        // a rectangle with two points inside
+        let (t, _) = triangulate(vec![
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 2.0),
+            (0.0, 2.0),
+            (1.0, 1.2),
+            (2.0, 0.7),
+        ]);
+        let svg = Svg::new()
+            .viewport(Viewport::fit(
+                Vertex::ZERO,
+                Vertex::new(3.0, 2.0),
+                150.0,
+                100.0,
+                0.0,
+            ))
+            .fill_with(|k| (k % 2 == 0).then(|| "#f00".to_string()))
+            .hull(Stroke::new("blue", 2.0))
+            .vertices(3.0, "a\"b")
+            .circumcircles(Stroke::new("gray", 0.5))
+            .voronoi(Stroke::new("green", 1.0))
+            .render(&t);
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="150""#));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches(r##"fill="#f00""##).count(), 3);
+        assert_eq!(svg.matches("<circle").count(), 6 + 6);
+        assert_eq!(svg.matches('M').count(), 11);
+        assert!(svg.contains(r#"<polygon points="0,0 150,0 150,100 0,100"/>"#));
+        assert!(svg.contains(r#"fill="a&quot;b""#));
+        // every site has a cell, cut off at the edge of the image
+        assert_eq!(svg.matches("<polygon").count(), 3 + 1 + 6);
+
+        // fitted into 800 by 800 pixels by default
+        let svg = Svg::new().edges(None).render(&t);
+        assert_eq!(svg.lines().count(), 2);
+        assert!(svg.contains(r#"viewBox="0 0 800 800""#));
+    }
+}
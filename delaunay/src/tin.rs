@@ -0,0 +1,461 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::locate::Location;
+use crate::math;
+use crate::types::{VertIndex, Vertex};
+use crate::util::orient2d_fast;
+use crate::{triangulate, HullContext, Triangulation};
+
+/// Terrain simplification by greedy insertion (Garland and Heckbert).
+///
+/// Starting from the corners of an elevation grid, repeatedly inserts the sample that
+/// the current triangulated surface misses by the most, until every sample is within
+/// the error bound or the vertex budget is spent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Simplifier {
+    max_error: f64,
+    max_vertices: usize,
+    origin: Vertex,
+    spacing: Vertex,
+}
+
+/// A triangulated irregular network: a triangulation with an elevation per vertex.
+#[derive(Debug)]
+pub struct Tin {
+    triangulation: Triangulation,
+    hull: HullContext,
+    elevations: Vec<f64>,
+    error: f64,
+}
+
+// the sample with the largest error in a triangle, as of the triangle's `stamp`
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    error: f64,
+    triangle: usize,
+    stamp: u32,
+    sample: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.error.total_cmp(&other.error)
+    }
+}
+
+impl Default for Simplifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simplifier {
+    /// A simplifier that keeps inserting until the surface matches every sample exactly.
+    pub fn new() -> Self {
+        Self {
+            max_error: 0.0,
+            max_vertices: usize::MAX,
+            origin: Vertex::ZERO,
+            spacing: Vertex::splat(1.0),
+        }
+    }
+
+    /// Stop once no sample is further than this above or below the surface.
+    pub fn max_error(mut self, max_error: f64) -> Self {
+        self.max_error = max_error;
+        self
+    }
+
+    /// Stop once the triangulation has this many vertices, the four corners included.
+    pub fn max_vertices(mut self, max_vertices: usize) -> Self {
+        self.max_vertices = max_vertices;
+        self
+    }
+
+    /// The position of the first sample. Defaults to the origin.
+    pub fn origin(mut self, origin: Vertex) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// The distance between neighboring samples along x and y, negative where the rows
+    /// or columns run down, as in north-up rasters. Defaults to one.
+    pub fn spacing(mut self, spacing: Vertex) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Simplifies a `width` by `height` grid of `elevations` in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// If the grid has fewer than 2 samples along either side, or `elevations` does not
+    /// hold `width * height` of them.
+    pub fn simplify(&self, width: usize, height: usize, elevations: &[f64]) -> Tin {
+        assert!(
+            width >= 2 && height >= 2,
+            "the grid needs at least 2 x 2 samples"
+        );
+        assert_eq!(elevations.len(), width * height);
+
+        let position = |s: usize| {
+            let (i, j) = (s % width, s / width);
+            self.origin + Vertex::new(i as f64 * self.spacing.x(), j as f64 * self.spacing.y())
+        };
+        let corners = [0, width - 1, width * height - 1, width * (height - 1)];
+        let (mut triangulation, mut hull) = triangulate(corners.map(position).to_vec());
+        // the corners are the first vertices
+        let mut heights = corners.map(|s| elevations[s]).to_vec();
+        let mut used = vec![false; elevations.len()];
+        for s in corners {
+            used[s] = true;
+        }
+
+        let grid = Grid {
+            width,
+            height,
+            origin: self.origin,
+            spacing: self.spacing,
+            elevations,
+        };
+        let mut stamps = Vec::new();
+        let mut queue = BinaryHeap::new();
+        let rescan = |t: &Triangulation,
+                      heights: &[f64],
+                      used: &[bool],
+                      stamps: &mut Vec<u32>,
+                      queue: &mut BinaryHeap<Candidate>,
+                      triangle: usize| {
+            stamps.resize(t.triangles.len() / 3, 0);
+            stamps[triangle] = stamps[triangle].wrapping_add(1);
+            if let Some((sample, error)) = grid.worst(t, heights, used, triangle) {
+                queue.push(Candidate {
+                    error,
+                    triangle,
+                    stamp: stamps[triangle],
+                    sample,
+                });
+            }
+        };
+        for triangle in 0..triangulation.triangles.len() / 3 {
+            rescan(
+                &triangulation,
+                &heights,
+                &used,
+                &mut stamps,
+                &mut queue,
+                triangle,
+            );
+        }
+
+        let mut error = 0.0;
+        while let Some(candidate) = queue.pop() {
+            if candidate.stamp != stamps[candidate.triangle] {
+                continue;
+            }
+            if candidate.error <= self.max_error || heights.len() >= self.max_vertices {
+                error = candidate.error;
+                break;
+            }
+
+            let p = position(candidate.sample);
+            let location = triangulation.locate_from(p, candidate.triangle);
+            let rebuilt = matches!(location, Location::Outside(_));
+            let (v, t) = triangulation.insert_at(&mut hull, p, location);
+            used[candidate.sample] = true;
+            if v.get() == heights.len() {
+                heights.push(elevations[candidate.sample]);
+            }
+
+            let changed = if rebuilt {
+                (0..triangulation.triangles.len() / 3).collect()
+            } else {
+                star(&triangulation, v, t)
+            };
+            for triangle in changed {
+                rescan(
+                    &triangulation,
+                    &heights,
+                    &used,
+                    &mut stamps,
+                    &mut queue,
+                    triangle,
+                );
+            }
+        }
+
+        Tin {
+            triangulation,
+            hull,
+            elevations: heights,
+            error,
+        }
+    }
+}
+
+struct Grid<'a> {
+    width: usize,
+    height: usize,
+    origin: Vertex,
+    spacing: Vertex,
+    elevations: &'a [f64],
+}
+
+impl Grid<'_> {
+    // the unused sample in a triangle, boundary included, furthest from its plane
+    fn worst(
+        &self,
+        t: &Triangulation,
+        heights: &[f64],
+        used: &[bool],
+        triangle: usize,
+    ) -> Option<(usize, f64)> {
+        let corners = [0, 1, 2].map(|k| t.triangles[3 * triangle + k]);
+        let [a, b, c] = corners.map(|v| t.points[v]);
+        let [za, zb, zc] = corners.map(|v| heights[v]);
+        let area = orient2d_fast(a, b, c);
+        if area <= 0.0 {
+            return None;
+        }
+
+        // the samples in the bounding box of the triangle
+        let (min, max) = (a.min(b).min(c), a.max(b).max(c));
+        let range = |lo: f64, hi: f64, origin: f64, step: f64, n: usize| {
+            // with a negative step the samples run from the high end down
+            let (lo, hi) = if step < 0.0 { (hi, lo) } else { (lo, hi) };
+            let first = math::ceil((lo - origin) / step).max(0.0) as usize;
+            let last = (math::floor((hi - origin) / step).max(-1.0) + 1.0) as usize;
+            first..last.min(n)
+        };
+        let columns = range(
+            min.x(),
+            max.x(),
+            self.origin.x(),
+            self.spacing.x(),
+            self.width,
+        );
+        let rows = range(
+            min.y(),
+            max.y(),
+            self.origin.y(),
+            self.spacing.y(),
+            self.height,
+        );
+
+        let mut worst = None;
+        for j in rows {
+            for i in columns.clone() {
+                let s = j * self.width + i;
+                if used[s] {
+                    continue;
+                }
+                let p = self.origin
+                    + Vertex::new(i as f64 * self.spacing.x(), j as f64 * self.spacing.y());
+                let (u, v, w) = (
+                    orient2d_fast(b, c, p),
+                    orient2d_fast(c, a, p),
+                    orient2d_fast(a, b, p),
+                );
+                if u < 0.0 || v < 0.0 || w < 0.0 {
+                    continue;
+                }
+                let z = (u * za + v * zb + w * zc) / area;
+                let error = (self.elevations[s] - z).abs();
+                if worst.is_none_or(|(_, e)| error > e) {
+                    worst = Some((s, error));
+                }
+            }
+        }
+        worst
+    }
+}
+
+// the triangles around vertex v, given one of them
+fn star(t: &Triangulation, v: VertIndex, triangle: usize) -> Vec<usize> {
+    let Some(k) = (0..3).find(|&k| t.triangles[3 * triangle + k] == v) else {
+        return Vec::new();
+    };
+    let start = Triangulation::prev_half_edge((3 * triangle + k).into());
+
+    // the walk around v stops at the hull, so turn the other way from `start` too
+    let mut star = t
+        .edges_around_point(start)
+        .map(|e| e.get() / 3)
+        .collect::<Vec<_>>();
+    let mut e = start;
+    while let Some(twin) = t.half_edges[e] {
+        e = Triangulation::prev_half_edge(twin);
+        if e == start {
+            break;
+        }
+        star.push(e.get() / 3);
+    }
+    star.sort_unstable();
+    star.dedup();
+    star
+}
+
+impl Tin {
+    pub fn triangulation(&self) -> &Triangulation {
+        &self.triangulation
+    }
+
+    /// The elevation of every vertex, indexed like the triangulation's points.
+    pub fn elevations(&self) -> &[f64] {
+        &self.elevations
+    }
+
+    /// The largest vertical distance between a remaining sample and the surface.
+    pub fn error(&self) -> f64 {
+        self.error
+    }
+
+    /// The triangulation, the hull it was built with, and the elevations.
+    pub fn into_parts(self) -> (Triangulation, HullContext, Vec<f64>) {
+        (self.triangulation, self.hull, self.elevations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terrain(width: usize, height: usize) -> Vec<f64> {
+        (0..width * height)
+            .map(|s| {
+                let (x, y) = ((s % width) as f64 / 8.0, (s / width) as f64 / 8.0);
+                x.sin() * y.cos() + 0.3 * (2.0 * x + y).sin()
+            })
+            .collect()
+    }
+
+    // the largest distance between a sample and the interpolated surface
+    fn max_error(tin: &Tin, width: usize, elevations: &[f64]) -> f64 {
+        let t = tin.triangulation();
+        let mut worst = 0.0_f64;
+        for (s, &elevation) in elevations.iter().enumerate() {
+            let p = Vertex::new((s % width) as f64, (s / width) as f64);
+            let z = match t.locate(p) {
+                Location::Vertex(v) => tin.elevations()[v],
+                Location::Triangle(tri) => interpolate(tin, tri, p),
+                Location::Edge(e) => interpolate(tin, e.get() / 3, p),
+                Location::Outside(_) => panic!("sample outside the TIN"),
+            };
+            worst = worst.max((z - elevation).abs());
+        }
+        worst
+    }
+
+    fn interpolate(tin: &Tin, tri: usize, p: Vertex) -> f64 {
+        let t = tin.triangulation();
+        let corners = [0, 1, 2].map(|k| t.triangles()[3 * tri + k]);
+        let [a, b, c] = corners.map(|v| t.points()[v]);
+        let [za, zb, zc] = corners.map(|v| tin.elevations()[v]);
+        let area = orient2d_fast(a, b, c);
+        (orient2d_fast(b, c, p) * za + orient2d_fast(c, a, p) * zb + orient2d_fast(a, b, p) * zc)
+            / area
+    }
+
+    #[test]
+    fn test_plane() {
+        let elevations = (0..20 * 10)
+            .map(|s| 2.0 * (s % 20) as f64 - (s / 20) as f64)
+            .collect::<Vec<_>>();
+        let tin = Simplifier::new()
+            .max_error(1e-9)
+            .simplify(20, 10, &elevations);
+        assert_eq!(tin.triangulation().points().len(), 4);
+        assert_eq!(tin.elevations(), [0.0, 38.0, 29.0, -9.0]);
+        assert!(tin.error() <= 1e-9);
+    }
+
+    #[test]
+    fn test_error_bound() {
+        let (width, height) = (65, 49);
+        let elevations = terrain(width, height);
+        let tin = Simplifier::new()
+            .max_error(0.02)
+            .simplify(width, height, &elevations);
+
+        let vertices = tin.triangulation().points().len();
+        assert!(vertices < width * height / 4, "{vertices} vertices");
+        assert!(tin.error() <= 0.02);
+        assert!(max_error(&tin, width, &elevations) <= 0.02 + 1e-12);
+        for p in tin.triangulation().points() {
+            assert!((0.0..=64.0).contains(&p.x()) && (0.0..=48.0).contains(&p.y()));
+        }
+    }
+
+    #[test]
+    fn test_vertex_budget() {
+        let (width, height) = (65, 49);
+        let elevations = terrain(width, height);
+        let coarse = Simplifier::new()
+            .max_vertices(40)
+            .simplify(width, height, &elevations);
+        let fine = Simplifier::new()
+            .max_vertices(200)
+            .simplify(width, height, &elevations);
+
+        assert_eq!(coarse.triangulation().points().len(), 40);
+        assert_eq!(fine.triangulation().points().len(), 200);
+        assert!(fine.error() < coarse.error());
+        assert!((max_error(&fine, width, &elevations) - fine.error()).abs() < 1e-12);
+
+        // every vertex sits on its sample
+        for (p, &z) in fine.triangulation().points().iter().zip(fine.elevations()) {
+            assert_eq!(elevations[p.y() as usize * width + p.x() as usize], z);
+        }
+    }
+
+    #[test]
+    fn test_spacing() {
+        let elevations = terrain(9, 9);
+        let tin = Simplifier::new()
+            .origin(Vertex::new(100.0, 200.0))
+            .spacing(Vertex::new(10.0, 5.0))
+            .max_vertices(30)
+            .simplify(9, 9, &elevations);
+        for p in tin.triangulation().points() {
+            let (i, j) = ((p.x() - 100.0) / 10.0, (p.y() - 200.0) / 5.0);
+            assert_eq!((i.fract(), j.fract()), (0.0, 0.0));
+            assert!((0.0..=8.0).contains(&i) && (0.0..=8.0).contains(&j));
+        }
+    }
+
+    #[test]
+    fn test_negative_spacing() {
+        let (width, height) = (65, 49);
+        let elevations = terrain(width, height);
+        // the same terrain with the rows from the top down
+        let flipped = (0..width * height)
+            .map(|s| elevations[(height - 1 - s / width) * width + s % width])
+            .collect::<Vec<_>>();
+        let tin = Simplifier::new()
+            .origin(Vertex::new(0.0, (height - 1) as f64))
+            .spacing(Vertex::new(1.0, -1.0))
+            .max_error(0.02)
+            .simplify(width, height, &flipped);
+
+        assert!(tin.triangulation().points().len() > 4);
+        assert!(tin.error() <= 0.02);
+        assert!(max_error(&tin, width, &elevations) <= 0.02 + 1e-12);
+    }
+}
@@ -0,0 +1,692 @@
+//! The file formats of Shewchuk's Triangle program: `.node`, `.ele`, `.poly` and `.neigh`.
+//!
+//! Files are parsed from strings and written with `Display`. Triangle numbers items
+//! from one or from zero; readers take the base from the first record of each file
+//! and return indices counted from zero, and writers count from one like Triangle does.
+//! Everything after a `#` on a line is a comment.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::types::{EdgeIndex, VertIndex, Vertex};
+use crate::{BuildError, ConstraintError, HullContext, Triangulation, TriangulationBuilder};
+
+/// Why a Triangle file could not be read. Lines count from one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatError {
+    /// The file ended before all the records its header announced.
+    UnexpectedEnd,
+    /// The line has too few fields, or one that is not a number.
+    Malformed(usize),
+    /// The file has points in this many dimensions instead of two.
+    Dimension(usize),
+    /// The record on this line is out of sequence or refers to a missing item.
+    Numbering(usize),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::UnexpectedEnd => write!(f, "the file ends early"),
+            FormatError::Malformed(line) => write!(f, "line {line} is malformed"),
+            FormatError::Dimension(d) => write!(f, "points have {d} dimensions instead of 2"),
+            FormatError::Numbering(line) => write!(f, "line {line} is numbered wrongly"),
+        }
+    }
+}
+
+impl core::error::Error for FormatError {}
+
+/// A `.node` file: points, each with the same number of attributes and maybe a marker.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeFile {
+    pub points: Vec<Vertex>,
+    /// How many attributes every point has.
+    pub attribute_count: usize,
+    /// The attributes of all points, `attribute_count` per point.
+    pub attributes: Vec<f64>,
+    /// A boundary marker per point, if the file has them.
+    pub markers: Option<Vec<i32>>,
+}
+
+/// An `.ele` file: triangles as three point indices, each with the same number of attributes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EleFile {
+    /// Corners in counterclockwise order. Second-order triangles keep their three corners.
+    pub triangles: Vec<[VertIndex; 3]>,
+    pub attribute_count: usize,
+    pub attributes: Vec<f64>,
+}
+
+/// A `.poly` file: a planar straight line graph for a constrained triangulation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PolyFile {
+    /// The points, or none if they are in a separate `.node` file.
+    pub nodes: NodeFile,
+    pub segments: Vec<(VertIndex, VertIndex)>,
+    pub segment_markers: Option<Vec<i32>>,
+    /// A point inside each hole.
+    pub holes: Vec<Vertex>,
+    pub regions: Vec<Region>,
+}
+
+/// A regional attribute and area constraint of a [`PolyFile`], for the region around `point`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub point: Vertex,
+    pub attribute: f64,
+    /// The largest area a triangle in the region may have; negative for no limit.
+    pub max_area: f64,
+}
+
+/// A `.neigh` file: for every triangle the triangle opposite each of its corners.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NeighFile {
+    pub neighbors: Vec<[Option<usize>; 3]>,
+}
+
+impl FromStr for NodeFile {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<Self, FormatError> {
+        let mut records = Records::new(s);
+        let (nodes, _) = read_nodes(&mut records)?;
+        Ok(nodes)
+    }
+}
+
+impl FromStr for EleFile {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<Self, FormatError> {
+        let mut records = Records::new(s);
+        let (line, header) = records.next()?;
+        let [count, corners, attribute_count] = header.numbers::<usize, 3>(line, 2)?;
+        if corners < 3 {
+            return Err(FormatError::Malformed(line));
+        }
+
+        let length = record_length(line, &[1, corners, attribute_count])?;
+        let mut ele = EleFile {
+            triangles: Vec::with_capacity(records.capacity(count, 1)),
+            attribute_count,
+            attributes: Vec::with_capacity(records.capacity(count, attribute_count)),
+        };
+        let mut numbering = Numbering::default();
+        for _ in 0..count {
+            let (line, fields) = records.next()?;
+            fields.expect(line, length)?;
+            let base = numbering.check(line, fields.number(line, 0)?)?;
+            let [a, b, c] = [1, 2, 3].map(|k| fields.index(line, k, base));
+            ele.triangles.push([a?, b?, c?]);
+            for k in 0..attribute_count {
+                ele.attributes.push(fields.number(line, 1 + corners + k)?);
+            }
+        }
+        Ok(ele)
+    }
+}
+
+impl FromStr for PolyFile {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<Self, FormatError> {
+        let mut records = Records::new(s);
+        let (nodes, node_base) = read_nodes(&mut records)?;
+
+        let (line, header) = records.next()?;
+        let [count, marked] = header.numbers::<usize, 2>(line, 1)?;
+        let mut poly = PolyFile {
+            nodes,
+            segments: Vec::with_capacity(records.capacity(count, 1)),
+            segment_markers: (marked > 0).then(|| Vec::with_capacity(records.capacity(count, 1))),
+            ..PolyFile::default()
+        };
+        let mut numbering = Numbering::default();
+        for _ in 0..count {
+            let (line, fields) = records.next()?;
+            fields.expect(line, 3 + usize::from(marked > 0))?;
+            let base = numbering.check(line, fields.number(line, 0)?)?;
+            // endpoints count like the points, wherever those are
+            let base = node_base.unwrap_or(base);
+            let (a, b) = (fields.index(line, 1, base)?, fields.index(line, 2, base)?);
+            if !poly.nodes.points.is_empty()
+                && [a, b].iter().any(|v| v.get() >= poly.nodes.points.len())
+            {
+                return Err(FormatError::Numbering(line));
+            }
+            poly.segments.push((a, b));
+            if let Some(markers) = &mut poly.segment_markers {
+                markers.push(fields.number(line, 3)?);
+            }
+        }
+
+        let (line, header) = records.next()?;
+        let [count] = header.numbers::<usize, 1>(line, 1)?;
+        let mut numbering = Numbering::default();
+        for _ in 0..count {
+            let (line, fields) = records.next()?;
+            fields.expect(line, 3)?;
+            numbering.check(line, fields.number(line, 0)?)?;
+            poly.holes.push(Vertex::new(
+                fields.number(line, 1)?,
+                fields.number(line, 2)?,
+            ));
+        }
+
+        // the regions are optional
+        let Some((line, header)) = records.next().ok() else {
+            return Ok(poly);
+        };
+        let [count] = header.numbers::<usize, 1>(line, 1)?;
+        let mut numbering = Numbering::default();
+        for _ in 0..count {
+            let (line, fields) = records.next()?;
+            fields.expect(line, 4)?;
+            numbering.check(line, fields.number(line, 0)?)?;
+            let point = Vertex::new(fields.number(line, 1)?, fields.number(line, 2)?);
+            let attribute = fields.number(line, 3)?;
+            // Triangle allows the area to be left out
+            let max_area = match fields.0.get(4) {
+                Some(_) => fields.number(line, 4)?,
+                None => -1.0,
+            };
+            poly.regions.push(Region {
+                point,
+                attribute,
+                max_area,
+            });
+        }
+        Ok(poly)
+    }
+}
+
+impl FromStr for NeighFile {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<Self, FormatError> {
+        let mut records = Records::new(s);
+        let (line, header) = records.next()?;
+        let [count, per_triangle] = header.numbers::<usize, 2>(line, 2)?;
+        if per_triangle != 3 {
+            return Err(FormatError::Malformed(line));
+        }
+
+        let mut neigh = NeighFile {
+            neighbors: Vec::with_capacity(records.capacity(count, 1)),
+        };
+        let mut numbering = Numbering::default();
+        for _ in 0..count {
+            let (line, fields) = records.next()?;
+            fields.expect(line, 4)?;
+            let base = numbering.check(line, fields.number(line, 0)?)?;
+            let neighbor = |k| match fields.number::<i64>(line, k)? {
+                n if n < 0 => Ok(None),
+                n => (n as usize)
+                    .checked_sub(base)
+                    .filter(|&t| t < count)
+                    .map(Some)
+                    .ok_or(FormatError::Numbering(line)),
+            };
+            neigh
+                .neighbors
+                .push([neighbor(1)?, neighbor(2)?, neighbor(3)?]);
+        }
+        Ok(neigh)
+    }
+}
+
+impl fmt::Display for NodeFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marked = self.markers.is_some();
+        writeln!(
+            f,
+            "{} 2 {} {}",
+            self.points.len(),
+            self.attribute_count,
+            u8::from(marked)
+        )?;
+        for (i, p) in self.points.iter().enumerate() {
+            write!(f, "{} {} {}", i + 1, p.x(), p.y())?;
+            let attributes = &self.attributes[i * self.attribute_count..][..self.attribute_count];
+            for a in attributes {
+                write!(f, " {a}")?;
+            }
+            if let Some(markers) = &self.markers {
+                write!(f, " {}", markers[i])?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for EleFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} 3 {}", self.triangles.len(), self.attribute_count)?;
+        for (i, [a, b, c]) in self.triangles.iter().enumerate() {
+            write!(
+                f,
+                "{} {} {} {}",
+                i + 1,
+                a.get() + 1,
+                b.get() + 1,
+                c.get() + 1
+            )?;
+            let attributes = &self.attributes[i * self.attribute_count..][..self.attribute_count];
+            for a in attributes {
+                write!(f, " {a}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PolyFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.nodes)?;
+        let marked = self.segment_markers.is_some();
+        writeln!(f, "{} {}", self.segments.len(), u8::from(marked))?;
+        for (i, (a, b)) in self.segments.iter().enumerate() {
+            write!(f, "{} {} {}", i + 1, a.get() + 1, b.get() + 1)?;
+            if let Some(markers) = &self.segment_markers {
+                write!(f, " {}", markers[i])?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "{}", self.holes.len())?;
+        for (i, p) in self.holes.iter().enumerate() {
+            writeln!(f, "{} {} {}", i + 1, p.x(), p.y())?;
+        }
+        if !self.regions.is_empty() {
+            writeln!(f, "{}", self.regions.len())?;
+            for (i, r) in self.regions.iter().enumerate() {
+                let (x, y) = (r.point.x(), r.point.y());
+                writeln!(f, "{} {x} {y} {} {}", i + 1, r.attribute, r.max_area)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for NeighFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} 3", self.neighbors.len())?;
+        for (i, neighbors) in self.neighbors.iter().enumerate() {
+            write!(f, "{}", i + 1)?;
+            for n in neighbors {
+                match n {
+                    Some(t) => write!(f, " {}", t + 1)?,
+                    None => write!(f, " -1")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl PolyFile {
+    /// The constrained Delaunay triangulation of the points with the segments as
+    /// constraints, and for each triangle whether a hole carves it out, see
+    /// [`Triangulation::in_holes`]. Regions are not applied. Points in a separate `.node`
+    /// file go in [`nodes`](Self::nodes) first; without them this fails with
+    /// [`BuildError::TooFewPoints`], and segments past the points fail with
+    /// [`ConstraintError::MissingPoint`].
+    pub fn triangulate(&self) -> Result<(Triangulation, HullContext, Vec<bool>), BuildError> {
+        let points = &self.nodes.points;
+        if points.len() < 3 {
+            return Err(BuildError::TooFewPoints(points.len()));
+        }
+        let mut endpoints = self.segments.iter().flat_map(|&(a, b)| [a, b]);
+        if let Some(v) = endpoints.find(|v| v.get() >= points.len()) {
+            return Err(ConstraintError::MissingPoint(v).into());
+        }
+        let (t, hull) = TriangulationBuilder::new(self.nodes.points.clone())
+            .constraints(self.segments.iter().map(|&(a, b)| (a.get(), b.get())))
+            .build()?;
+        let carved = t.in_holes(&self.holes);
+        Ok((t, hull, carved))
+    }
+}
+
+impl Triangulation {
+    /// The points as a `.node` file, with marker 1 on the hull and 0 elsewhere.
+    pub fn to_node_file(&self) -> NodeFile {
+        let mut markers = vec![0; self.points.len()];
+        for &v in &self.hull {
+            markers[v.get()] = 1;
+        }
+        NodeFile {
+            points: self.points.clone(),
+            markers: Some(markers),
+            ..NodeFile::default()
+        }
+    }
+
+    /// The triangles as an `.ele` file, turned counterclockwise as Triangle expects.
+    pub fn to_ele_file(&self) -> EleFile {
+        EleFile {
+            triangles: self
+                .triangles
+                .chunks_exact(3)
+                .map(|t| [t[0], t[2], t[1]])
+                .collect(),
+            ..EleFile::default()
+        }
+    }
+
+    /// The points and the constraints as segments, see [`constraints`](Self::constraints).
+    pub fn to_poly_file(&self) -> PolyFile {
+        PolyFile {
+            nodes: self.to_node_file(),
+            segments: self.constraints().collect(),
+            ..PolyFile::default()
+        }
+    }
+
+    /// The neighbors of the triangles of [`to_ele_file`](Self::to_ele_file).
+    pub fn to_neigh_file(&self) -> NeighFile {
+        let across = |e: usize| self.half_edges[EdgeIndex::from(e)].map(|twin| twin.get() / 3);
+        NeighFile {
+            neighbors: (0..self.triangles.len() / 3)
+                // the corners are written as 0, 2, 1, and edge 3t + k runs from corner k to k + 1
+                .map(|t| [across(3 * t + 1), across(3 * t), across(3 * t + 2)])
+                .collect(),
+        }
+    }
+}
+
+// the point section shared by .node and .poly files, and the base of its numbering
+// unless the points are elsewhere
+fn read_nodes(records: &mut Records) -> Result<(NodeFile, Option<usize>), FormatError> {
+    let (line, header) = records.next()?;
+    let [count, dimension, attribute_count, marked] = header.numbers::<usize, 4>(line, 1)?;
+    if count > 0 && dimension != 2 {
+        return Err(FormatError::Dimension(dimension));
+    }
+    let marked = marked > 0;
+
+    let length = record_length(line, &[3, attribute_count, usize::from(marked)])?;
+    let mut nodes = NodeFile {
+        points: Vec::with_capacity(records.capacity(count, 1)),
+        attribute_count,
+        attributes: Vec::with_capacity(records.capacity(count, attribute_count)),
+        markers: marked.then(|| Vec::with_capacity(records.capacity(count, 1))),
+    };
+    let mut numbering = Numbering::default();
+    for _ in 0..count {
+        let (line, fields) = records.next()?;
+        fields.expect(line, length)?;
+        numbering.check(line, fields.number(line, 0)?)?;
+        nodes.points.push(Vertex::new(
+            fields.number(line, 1)?,
+            fields.number(line, 2)?,
+        ));
+        for k in 0..attribute_count {
+            nodes.attributes.push(fields.number(line, 3 + k)?);
+        }
+        if let Some(markers) = &mut nodes.markers {
+            markers.push(fields.number(line, 3 + attribute_count)?);
+        }
+    }
+    Ok((nodes, numbering.base))
+}
+
+// the lines of a file with fields, skipping comments and blank lines
+struct Records<'a> {
+    lines: core::iter::Enumerate<core::str::Lines<'a>>,
+    len: usize,
+}
+
+struct Fields<'a>(Vec<&'a str>);
+
+impl<'a> Records<'a> {
+    fn new(s: &'a str) -> Self {
+        Self {
+            lines: s.lines().enumerate(),
+            len: s.len(),
+        }
+    }
+
+    // room for `count` items of `size` values each, though no more than the input
+    // could hold, whatever its header claims
+    fn capacity(&self, count: usize, size: usize) -> usize {
+        count.saturating_mul(size).min(self.len)
+    }
+
+    fn next(&mut self) -> Result<(usize, Fields<'a>), FormatError> {
+        for (number, line) in self.lines.by_ref() {
+            let line = line.split('#').next().unwrap_or("");
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            if !fields.is_empty() {
+                return Ok((number + 1, Fields(fields)));
+            }
+        }
+        Err(FormatError::UnexpectedEnd)
+    }
+}
+
+// the number of fields in a record of these parts, which a header can make overflow
+fn record_length(line: usize, parts: &[usize]) -> Result<usize, FormatError> {
+    parts
+        .iter()
+        .try_fold(0usize, |sum, &n| sum.checked_add(n))
+        .ok_or(FormatError::Malformed(line))
+}
+
+impl Fields<'_> {
+    fn expect(&self, line: usize, count: usize) -> Result<(), FormatError> {
+        if self.0.len() < count {
+            return Err(FormatError::Malformed(line));
+        }
+        Ok(())
+    }
+
+    fn number<T: FromStr>(&self, line: usize, k: usize) -> Result<T, FormatError> {
+        self.0
+            .get(k)
+            .and_then(|field| field.parse().ok())
+            .ok_or(FormatError::Malformed(line))
+    }
+
+    // the first N fields of a header, of which the first `required` must be there
+    // and the rest default to zero
+    fn numbers<T: FromStr + Default + Copy, const N: usize>(
+        &self,
+        line: usize,
+        required: usize,
+    ) -> Result<[T; N], FormatError> {
+        self.expect(line, required)?;
+        let mut numbers = [T::default(); N];
+        for (k, number) in numbers.iter_mut().enumerate().take(self.0.len()) {
+            *number = self.number(line, k)?;
+        }
+        Ok(numbers)
+    }
+
+    fn index(&self, line: usize, k: usize, base: usize) -> Result<VertIndex, FormatError> {
+        self.number::<usize>(line, k)?
+            .checked_sub(base)
+            .filter(|&i| u32::try_from(i).is_ok())
+            .map(VertIndex::from)
+            .ok_or(FormatError::Numbering(line))
+    }
+}
+
+// checks that records are numbered consecutively from zero or one
+#[derive(Default)]
+struct Numbering {
+    base: Option<usize>,
+    count: usize,
+}
+
+impl Numbering {
+    fn check(&mut self, line: usize, number: usize) -> Result<usize, FormatError> {
+        let base = *self.base.get_or_insert(number);
+        if base > 1 || number != base + self.count {
+            return Err(FormatError::Numbering(line));
+        }
+        self.count += 1;
+        Ok(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{generate, triangulate};
+
+    const NODE: &str = include_str!("../tests/fixtures/box.node");
+    const ELE: &str = include_str!("../tests/fixtures/box.ele");
+    const POLY: &str = include_str!("../tests/fixtures/box.poly");
+    const NEIGH: &str = include_str!("../tests/fixtures/box.neigh");
+
+    // parses the text, and checks that writing and parsing again gives the same
+    fn round_trip<T>(text: &str) -> T
+    where
+        T: FromStr<Err = FormatError> + fmt::Display + Clone + PartialEq + fmt::Debug,
+    {
+        let parsed: T = text.parse().unwrap();
+        assert_eq!(parsed.to_string().parse::<T>(), Ok(parsed.clone()));
+        parsed
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let node: NodeFile = round_trip(NODE);
+        assert_eq!(node.points.len(), 5);
+        assert_eq!(node.points[4], Vertex::new(1.0, 1.5));
+        assert_eq!(node.attributes, [0.5, 1.0, 1.5, 2.0, -3.25]);
+        assert_eq!(node.markers, Some(vec![1, 1, 2, 1, 0]));
+
+        let ele: EleFile = round_trip(ELE);
+        assert_eq!(ele.triangles.len(), 4);
+        assert_eq!(ele.triangles[0], [0.into(), 1.into(), 4.into()]);
+        assert_eq!(ele.attribute_count, 0);
+
+        let neigh: NeighFile = round_trip(NEIGH);
+        assert_eq!(neigh.neighbors[0], [Some(1), Some(3), None]);
+
+        let poly: PolyFile = round_trip(POLY);
+        assert_eq!(poly.nodes.points.len(), 8);
+        assert_eq!(poly.segments.len(), 8);
+        assert_eq!(poly.segment_markers, Some(vec![1, 1, 1, 1, 2, 2, 2, 2]));
+        assert_eq!(poly.holes, [Vertex::new(1.5, 1.5)]);
+        assert_eq!(poly.regions[0].max_area, 0.25);
+
+        assert_eq!(
+            "2 2 0 0\n1 0 0\n1 1 1\n".parse::<NodeFile>(),
+            Err(FormatError::Numbering(3))
+        );
+        assert_eq!(
+            "2 3 0 0\n".parse::<NodeFile>(),
+            Err(FormatError::Dimension(3))
+        );
+        assert_eq!(
+            "1 3 0\n0 0 1\n".parse::<EleFile>(),
+            Err(FormatError::Malformed(2))
+        );
+        assert_eq!(
+            "2 3 0\n0 0 1 2\n".parse::<EleFile>(),
+            Err(FormatError::UnexpectedEnd)
+        );
+
+        // headers claiming more than there is fail without allocating it
+        assert_eq!(
+            "18446744073709551615 2 0 0".parse::<NodeFile>(),
+            Err(FormatError::UnexpectedEnd)
+        );
+        assert_eq!(
+            "3 2 9223372036854775807 0".parse::<NodeFile>(),
+            Err(FormatError::UnexpectedEnd)
+        );
+        assert_eq!(
+            "3 2 18446744073709551615 1".parse::<NodeFile>(),
+            Err(FormatError::Malformed(1))
+        );
+        assert_eq!(
+            "9223372036854775807 3 9223372036854775807".parse::<EleFile>(),
+            Err(FormatError::UnexpectedEnd)
+        );
+        assert_eq!(
+            "1 18446744073709551615 1".parse::<EleFile>(),
+            Err(FormatError::Malformed(1))
+        );
+        // indices that don't fit in 32 bits
+        assert_eq!(
+            "1 3 0\n1 1 2 5000000000\n".parse::<EleFile>(),
+            Err(FormatError::Numbering(2))
+        );
+        assert_eq!(
+            "0 2 0 0\n1 0\n1 1 5000000000\n0\n".parse::<PolyFile>(),
+            Err(FormatError::Numbering(3))
+        );
+    }
+
+    #[test]
+    fn test_triangulation_files() {
+        let points = generate::uniform(60, Vertex::ZERO, Vertex::splat(1.0), 14);
+        let (t, _) = triangulate(points);
+        let node = t.to_node_file();
+        let ele = t.to_ele_file();
+        let neigh = t.to_neigh_file();
+        assert_eq!(node.to_string().parse(), Ok(node.clone()));
+        assert_eq!(ele.to_string().parse(), Ok(ele.clone()));
+        assert_eq!(neigh.to_string().parse(), Ok(neigh.clone()));
+
+        for (t, corners) in ele.triangles.iter().enumerate() {
+            // counterclockwise with y up
+            let [a, b, c] = corners.map(|v| node.points[v.get()]);
+            assert!((b - a).x() * (c - a).y() - (b - a).y() * (c - a).x() > 0.0);
+            // the neighbor opposite a corner shares the other two
+            for (k, n) in neigh.neighbors[t].iter().enumerate() {
+                match n {
+                    Some(n) => {
+                        let shared = |v| ele.triangles[*n].contains(v);
+                        assert!(!shared(&corners[k]));
+                        assert!(shared(&corners[(k + 1) % 3]) && shared(&corners[(k + 2) % 3]));
+                    }
+                    None => {
+                        let marked = |v: VertIndex| node.markers.as_ref().unwrap()[v.get()] == 1;
+                        assert!(marked(corners[(k + 1) % 3]) && marked(corners[(k + 2) % 3]));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_poly_constraints() {
+        let poly: PolyFile = POLY.parse().unwrap();
+        let (t, _, carved) = poly.triangulate().unwrap();
+        assert_eq!(t.constraints().count(), 8);
+        // the inner square is the hole
+        assert_eq!(carved.len(), 10);
+        for (k, &carved) in carved.iter().enumerate() {
+            let inner = t.triangles()[3 * k..3 * k + 3].iter().all(|v| v.get() >= 4);
+            assert_eq!(carved, inner);
+        }
+        assert_eq!(carved.iter().filter(|&&c| c).count(), 2);
+        let again: PolyFile = t.to_poly_file().to_string().parse().unwrap();
+        assert_eq!(again.segments, t.constraints().collect::<Vec<_>>());
+        assert_eq!(again.nodes.points, poly.nodes.points);
+
+        // points in a separate .node file that were never filled in
+        let mut poly: PolyFile = "0 2 0 0\n1 0\n0 0 1\n0\n".parse().unwrap();
+        assert_eq!(poly.triangulate().err(), Some(BuildError::TooFewPoints(0)));
+        poly.nodes.points = vec![Vertex::ZERO, Vertex::new(1.0, 0.0)];
+        assert_eq!(poly.triangulate().err(), Some(BuildError::TooFewPoints(2)));
+        poly.nodes.points.push(Vertex::new(0.0, 1.0));
+        poly.segments.push((1.into(), 3.into()));
+        assert_eq!(
+            poly.triangulate().err(),
+            Some(ConstraintError::MissingPoint(3.into()).into())
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use std::ops::{Add, Deref, Div, Index, IndexMut, Mul, Sub};
+use alloc::vec::Vec;
+use core::fmt;
+use core::num::NonZeroU32;
+use core::ops::{Add, Div, Index, IndexMut, Mul, Sub};
+
+use crate::math;
 
 /// A vertex in 2D space.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -51,6 +56,10 @@ impl Vertex {
         self.x * self.x + self.y * self.y
     }
 
+    pub fn length(&self) -> f64 {
+        math::sqrt(self.length_squared())
+    }
+
     pub fn min(&self, other: Vertex) -> Self {
         Self {
             x: self.x.min(other.x),
@@ -144,26 +153,29 @@ impl Div<Vertex> for f64 {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct VertIndex(usize);
+/// The index of a point, stored in 32 bits. Converting a larger index panics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VertIndex(u32);
 
-impl Default for VertIndex {
-    fn default() -> Self {
-        Self(Default::default())
+impl VertIndex {
+    pub const fn get(self) -> usize {
+        self.0 as usize
     }
 }
 
 impl From<usize> for VertIndex {
     fn from(value: usize) -> Self {
-        Self(value)
+        assert!(
+            value <= u32::MAX as usize,
+            "point index {value} needs more than 32 bits"
+        );
+        Self(value as u32)
     }
 }
 
-impl Deref for VertIndex {
-    type Target = usize;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl From<VertIndex> for usize {
+    fn from(value: VertIndex) -> Self {
+        value.get()
     }
 }
 
@@ -171,36 +183,67 @@ impl<T> Index<VertIndex> for Vec<T> {
     type Output = T;
 
     fn index(&self, index: VertIndex) -> &Self::Output {
-        &self[index.0]
+        &self[index.get()]
     }
 }
 
 impl<T> IndexMut<VertIndex> for Vec<T> {
     fn index_mut(&mut self, index: VertIndex) -> &mut Self::Output {
-        &mut self[index.0]
+        &mut self[index.get()]
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct EdgeIndex(usize);
+impl<T> Index<VertIndex> for [T] {
+    type Output = T;
+
+    fn index(&self, index: VertIndex) -> &Self::Output {
+        &self[index.get()]
+    }
+}
+
+impl<T> IndexMut<VertIndex> for [T] {
+    fn index_mut(&mut self, index: VertIndex) -> &mut Self::Output {
+        &mut self[index.get()]
+    }
+}
+
+/// The index of a half-edge, stored in 32 bits as one more than the index, so that
+/// zero is free to mean `None` and a twin, `Option<EdgeIndex>`, takes 4 bytes as well.
+/// Converting an index that doesn't fit panics.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeIndex(NonZeroU32);
+
+impl EdgeIndex {
+    pub const fn get(self) -> usize {
+        self.0.get() as usize - 1
+    }
+}
+
+impl fmt::Debug for EdgeIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EdgeIndex").field(&self.get()).finish()
+    }
+}
 
 impl Default for EdgeIndex {
     fn default() -> Self {
-        Self(Default::default())
+        Self::from(0)
     }
 }
 
 impl From<usize> for EdgeIndex {
     fn from(value: usize) -> Self {
-        Self(value)
+        assert!(
+            value < u32::MAX as usize,
+            "half-edge index {value} needs more than 32 bits"
+        );
+        Self(NonZeroU32::MIN.saturating_add(value as u32))
     }
 }
 
-impl Deref for EdgeIndex {
-    type Target = usize;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl From<EdgeIndex> for usize {
+    fn from(value: EdgeIndex) -> Self {
+        value.get()
     }
 }
 
@@ -208,13 +251,27 @@ impl<T> Index<EdgeIndex> for Vec<T> {
     type Output = T;
 
     fn index(&self, index: EdgeIndex) -> &Self::Output {
-        &self[index.0]
+        &self[index.get()]
     }
 }
 
 impl<T> IndexMut<EdgeIndex> for Vec<T> {
     fn index_mut(&mut self, index: EdgeIndex) -> &mut Self::Output {
-        &mut self[index.0]
+        &mut self[index.get()]
+    }
+}
+
+impl<T> Index<EdgeIndex> for [T] {
+    type Output = T;
+
+    fn index(&self, index: EdgeIndex) -> &Self::Output {
+        &self[index.get()]
+    }
+}
+
+impl<T> IndexMut<EdgeIndex> for [T] {
+    fn index_mut(&mut self, index: EdgeIndex) -> &mut Self::Output {
+        &mut self[index.get()]
     }
 }
 
@@ -276,3 +333,69 @@ impl TriTriple {
         self.c
     }
 }
+
+/// A vertex in 2D space carrying a weight, as used by regular triangulations
+/// and power diagrams. The power distance from a point `p` is
+/// `|p - v|² - w`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedVertex {
+    x: f64,
+    y: f64,
+    w: f64,
+}
+
+impl WeightedVertex {
+    pub const fn new(x: f64, y: f64, w: f64) -> Self {
+        Self { x, y, w }
+    }
+
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    pub fn w(&self) -> f64 {
+        self.w
+    }
+
+    pub fn vertex(&self) -> Vertex {
+        Vertex::new(self.x, self.y)
+    }
+
+    pub fn power_distance(&self, p: Vertex) -> f64 {
+        self.vertex().distance_squared(p) - self.w
+    }
+}
+
+impl Default for WeightedVertex {
+    fn default() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+}
+
+impl From<Vertex> for WeightedVertex {
+    fn from(value: Vertex) -> Self {
+        Self::new(value.x, value.y, 0.0)
+    }
+}
+
+impl From<(f64, f64, f64)> for WeightedVertex {
+    fn from(value: (f64, f64, f64)) -> Self {
+        Self::new(value.0, value.1, value.2)
+    }
+}
+
+impl From<[f64; 3]> for WeightedVertex {
+    fn from(value: [f64; 3]) -> Self {
+        Self::new(value[0], value[1], value[2])
+    }
+}
+
+impl From<WeightedVertex> for Vertex {
+    fn from(value: WeightedVertex) -> Self {
+        value.vertex()
+    }
+}
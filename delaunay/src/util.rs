@@ -1,4 +1,7 @@
-use crate::types::{VertTriple, Vertex};
+use alloc::vec::Vec;
+
+use crate::math;
+use crate::types::{VertTriple, Vertex, WeightedVertex};
 
 pub(crate) fn seed_triangle(
     points: &[Vertex],
@@ -26,12 +29,12 @@ pub(crate) fn seed_triangle(
         },
     );
 
-    // Find the closest point to the seed
+    // Find the closest point to the seed, other than a duplicate of it
     let (mut i1, mut p1, _) = points.iter().enumerate().filter(|(i, _)| *i != i0).fold(
         (0, Vertex::NAN, f64::INFINITY),
         |(i_min, p_min, d_min), (i, p)| {
             let d = p.distance_squared(p0);
-            if d < d_min {
+            if d < d_min && d > 0.0 {
                 (i, *p, d)
             } else {
                 (i_min, p_min, d_min)
@@ -62,8 +65,8 @@ pub(crate) fn seed_triangle(
 
     // swap the order of the seed points for counter-clockwise orientation
     if orient2d_fast(p0.into(), p1.into(), p2.into()) < 0.0 {
-        std::mem::swap(&mut i1, &mut i2);
-        std::mem::swap(&mut p1, &mut p2);
+        core::mem::swap(&mut i1, &mut i2);
+        core::mem::swap(&mut p1, &mut p2);
     }
 
     Ok((
@@ -114,6 +117,161 @@ pub(crate) fn in_circle(a: Vertex, b: Vertex, c: Vertex, p: Vertex) -> bool {
         < 0.0
 }
 
+// lifted incircle test on the power distance: negative when p conflicts with the orthocircle of abc
+pub(crate) fn power_circle(
+    a: WeightedVertex,
+    b: WeightedVertex,
+    c: WeightedVertex,
+    p: WeightedVertex,
+) -> f64 {
+    let d = a.vertex() - p.vertex();
+    let e = b.vertex() - p.vertex();
+    let f = c.vertex() - p.vertex();
+
+    let ap = d.length_squared() - a.w() + p.w();
+    let bp = e.length_squared() - b.w() + p.w();
+    let cp = f.length_squared() - c.w() + p.w();
+
+    d.x() * (e.y() * cp - bp * f.y()) - d.y() * (e.x() * cp - bp * f.x())
+        + ap * (e.x() * f.y() - e.y() * f.x())
+}
+
+pub(crate) fn in_power_circle(
+    a: WeightedVertex,
+    b: WeightedVertex,
+    c: WeightedVertex,
+    p: WeightedVertex,
+) -> bool {
+    power_circle(a, b, c, p) < 0.0
+}
+
+// the point with equal power distance to all three weighted vertices
+pub(crate) fn power_center(a: WeightedVertex, b: WeightedVertex, c: WeightedVertex) -> Vertex {
+    let d = b.vertex() - a.vertex();
+    let e = c.vertex() - a.vertex();
+
+    let bl = d.length_squared() - b.w() + a.w();
+    let cl = e.length_squared() - c.w() + a.w();
+    let dia = 0.5 / (d.x() * e.y() - d.y() * e.x());
+
+    let x = a.x() + (e.y() * bl - d.y() * cl) * dia;
+    let y = a.y() + (d.x() * cl - e.x() * bl) * dia;
+
+    Vertex::new(x, y)
+}
+
+// Andrew's monotone chain over a subset of the points. The hull is returned in the same
+// winding as `Triangulation::hull`; collinear boundary points are kept on request.
+pub(crate) fn convex_hull_of(
+    points: &[Vertex],
+    mut ids: Vec<usize>,
+    collinear: bool,
+) -> Vec<usize> {
+    ids.sort_by(|&a, &b| {
+        points[a]
+            .x()
+            .total_cmp(&points[b].x())
+            .then(points[a].y().total_cmp(&points[b].y()))
+    });
+    ids.dedup_by(|a, b| points[*a] == points[*b]);
+    if ids.len() < 3 {
+        return ids;
+    }
+
+    // orient2d_fast is positive for a clockwise turn, which is the winding we want
+    let turns = |o: f64| if collinear { o < 0.0 } else { o <= 0.0 };
+    let mut hull: Vec<usize> = Vec::with_capacity(ids.len() + 1);
+    for &i in ids.iter() {
+        while hull.len() >= 2
+            && turns(orient2d_fast(
+                points[hull[hull.len() - 2]],
+                points[hull[hull.len() - 1]],
+                points[i],
+            ))
+        {
+            hull.pop();
+        }
+        hull.push(i);
+    }
+    let lower = hull.len() + 1;
+    for &i in ids.iter().rev().skip(1) {
+        while hull.len() >= lower
+            && turns(orient2d_fast(
+                points[hull[hull.len() - 2]],
+                points[hull[hull.len() - 1]],
+                points[i],
+            ))
+        {
+            hull.pop();
+        }
+        hull.push(i);
+    }
+    hull.pop();
+
+    hull
+}
+
+// shoelace formula: positive for counter-clockwise polygons
+pub(crate) fn polygon_area(polygon: &[Vertex]) -> f64 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            a.x() * b.y() - b.x() * a.y()
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+pub(crate) fn polygon_centroid(polygon: &[Vertex]) -> Vertex {
+    let n = polygon.len();
+    let (sum, area) = (0..n).fold((Vertex::ZERO, 0.0), |(sum, area), i| {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        let cross = a.x() * b.y() - b.x() * a.y();
+        (sum + (a + b) * cross, area + cross)
+    });
+    sum / (3.0 * area)
+}
+
+// even-odd rule, for polygons of any winding
+pub(crate) fn point_in_polygon(p: Vertex, polygon: &[Vertex]) -> bool {
+    let n = polygon.len();
+    (0..n).fold(false, |inside, i| {
+        let (a, b) = (polygon[i], polygon[(i + n - 1) % n]);
+        if (a.y() > p.y()) != (b.y() > p.y())
+            && p.x() < a.x() + (b.x() - a.x()) * (p.y() - a.y()) / (b.y() - a.y())
+        {
+            !inside
+        } else {
+            inside
+        }
+    })
+}
+
+// Sutherland-Hodgman: clips any polygon to a convex region of either winding
+pub(crate) fn clip_polygon(subject: &[Vertex], region: &[Vertex]) -> Vec<Vertex> {
+    let winding = polygon_area(region).signum();
+    let mut output = subject.to_vec();
+
+    for k in 0..region.len() {
+        let (a, b) = (region[k], region[(k + 1) % region.len()]);
+        let side = |p: Vertex| orient2d_fast(a, b, p) * winding;
+        let input = core::mem::take(&mut output);
+
+        for (i, &q) in input.iter().enumerate() {
+            let p = input[(i + input.len() - 1) % input.len()];
+            let (sp, sq) = (side(p), side(q));
+            if (sp <= 0.0) != (sq <= 0.0) {
+                output.push(p + (q - p) * (sp / (sp - sq)));
+            }
+            if sq <= 0.0 {
+                output.push(q);
+            }
+        }
+    }
+    output
+}
+
 // monotonically increases with real angle, but doesn't need expensive trigonometry
 pub(crate) fn pseudo_angle(dx: f64, dy: f64) -> f64 {
     let p = dx / (dx.abs() + dy.abs());
@@ -127,7 +285,7 @@ pub(crate) fn pseudo_angle(dx: f64, dy: f64) -> f64 {
 }
 
 pub(crate) fn hash_key(p: Vertex, c: Vertex, hash_size: f64) -> usize {
-    ((pseudo_angle(p.x() - c.x(), p.y() - c.y()) * hash_size).floor() % hash_size) as usize
+    (math::floor(pseudo_angle(p.x() - c.x(), p.y() - c.y()) * hash_size) % hash_size) as usize
 }
 
 pub fn orient2d_fast(a: Vertex, b: Vertex, c: Vertex) -> f64 {
@@ -0,0 +1,83 @@
+use core::fmt;
+
+use crate::types::EdgeIndex;
+use crate::Triangulation;
+
+/// The first inconsistency [`Triangulation::validate`] found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The twin of this half-edge does not point back, or runs between other points.
+    Twin(EdgeIndex),
+    /// The triangle with this index is flat or turned the wrong way.
+    Orientation(usize),
+    /// The number of half-edges without a twin differs from the length of the hull.
+    Hull,
+    /// This edge is neither constrained nor locally Delaunay.
+    NotDelaunay(EdgeIndex),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Twin(e) => write!(f, "half-edge {} has a broken twin", e.get()),
+            ValidationError::Orientation(t) => write!(f, "triangle {t} is not oriented"),
+            ValidationError::Hull => write!(f, "the hull does not match the boundary edges"),
+            ValidationError::NotDelaunay(e) => write!(f, "edge {} is not Delaunay", e.get()),
+        }
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+impl Triangulation {
+    /// Checks that twins agree, every triangle is positively oriented, the hull matches
+    /// the boundary and every unconstrained edge is locally Delaunay.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for e in (0..self.triangles.len()).map(EdgeIndex::from) {
+            if let Some(twin) = self.half_edges[e] {
+                if self.half_edges[twin] != Some(e)
+                    || self.triangles[e] != self.triangles[Self::next_half_edge(twin)]
+                {
+                    return Err(ValidationError::Twin(e));
+                }
+            }
+        }
+
+        for (t, corners) in self.triangles.chunks_exact(3).enumerate() {
+            let [a, b, c] = [0, 1, 2].map(|k| self.points[corners[k]]);
+            if self.orient(a, b, c) <= 0.0 {
+                return Err(ValidationError::Orientation(t));
+            }
+        }
+
+        let boundary = self.half_edges.iter().filter(|h| h.is_none()).count();
+        if !self.triangles.is_empty() && boundary != self.hull.len() {
+            return Err(ValidationError::Hull);
+        }
+
+        (0..self.triangles.len())
+            .map(EdgeIndex::from)
+            .find(|&e| !self.is_locally_delaunay(e))
+            .map_or(Ok(()), |e| Err(ValidationError::NotDelaunay(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, triangulate, Vertex};
+
+    #[test]
+    fn test_validate() {
+        let points = generate::uniform(100, Vertex::ZERO, Vertex::splat(1.0), 5);
+        let (mut t, _) = triangulate(points);
+        assert_eq!(t.validate(), Ok(()));
+
+        let flipped = (0..t.triangles.len()).find_map(|e| t.flip(e.into()).ok());
+        assert!(flipped.is_some());
+        assert!(matches!(t.validate(), Err(ValidationError::NotDelaunay(_))));
+
+        t.triangles.swap(0, 1);
+        assert!(t.validate().is_err());
+    }
+}
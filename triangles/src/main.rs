@@ -10,7 +10,7 @@ use bevy::{
         },
     },
 };
-use delaunay::triangulate;
+use delaunay::{generate, triangulate, Vertex};
 
 fn main() {
     App::new()
@@ -25,19 +25,9 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<LineMaterial>>,
 ) {
-    let points = [
-        // Outer Square
-        [0.0, 0.0, 0.0],
-        [1.0, 0.0, 0.0],
-        [0.0, 1.0, 0.0],
-        [1.0, 1.0, 0.0],
-        // Inner Triangle
-        [0.3, 0.4, 1.0],
-        [0.5, 0.7, 1.0],
-        [0.7, 0.4, 1.0],
-    ];
+    let points = generate::poisson_disk(Vertex::splat(-2.0), Vertex::splat(2.0), 0.15, 42);
 
-    let (triangulation, _) = triangulate(points.map(|v| [v[0], v[1]]).to_vec());
+    let (triangulation, _) = triangulate(points);
 
     // Spawn a list of lines with start and end points for each lines
     commands.spawn(MaterialMeshBundle {